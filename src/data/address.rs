@@ -0,0 +1,241 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{
+    data::{Output, UnconfirmedTxs},
+    token::deserialize_decimals,
+};
+
+#[cfg(feature = "server")]
+use crate::{data::config::node, utils::address::is_valid_address};
+#[cfg(feature = "server")]
+use serde_json::Value;
+
+/* Address endpoints below all require the node's extra indexer */
+#[cfg(feature = "server")]
+const NODE_BALANCE_FOR_ADDRESS: &str = "/blockchain/balanceForAddress/";
+#[cfg(feature = "server")]
+const NODE_TRANSACTIONS_BY_ADDRESS: &str = "/blockchain/transaction/byAddress/";
+#[cfg(feature = "server")]
+const NODE_UNSPENT_BOXES_BY_ADDRESS: &str = "/blockchain/box/unspent/byAddress/";
+
+pub const HISTORY_PAGE_SIZE: u32 = 20;
+
+/* Most recent unspent boxes shown on the address page */
+pub const UNSPENT_BOXES_LIMIT: u32 = 50;
+
+/* Number of most recent transactions the balance chart is rebuilt from */
+#[cfg(feature = "server")]
+const BALANCE_CHART_TRANSACTIONS: u32 = 100;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalance {
+    pub token_id: String,
+    pub amount: u64,
+    #[serde(default, deserialize_with = "deserialize_decimals")]
+    pub decimals: u32,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+    #[serde(default)]
+    pub nano_ergs: u64,
+    #[serde(default)]
+    pub tokens: Vec<TokenBalance>,
+}
+
+/* Confirmed nanoERG balance right after a transaction */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalancePoint {
+    pub height: u32,
+    pub timestamp: u64,
+    pub nano_ergs: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressDetails {
+    pub address: String,
+    pub confirmed: Balance,
+    /* Balance of the address' boxes in the mempool, as reported by the node */
+    pub unconfirmed: Balance,
+    /* Newest first, at most UNSPENT_BOXES_LIMIT */
+    pub unspent_boxes: Vec<Output>,
+    /* Oldest first, over the last BALANCE_CHART_TRANSACTIONS transactions */
+    pub balance_history: Vec<BalancePoint>,
+}
+
+/* A confirmed transaction touching the address */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressTransaction {
+    pub transaction: UnconfirmedTxs,
+    pub inclusion_height: u32,
+    pub timestamp: u64,
+    /* nanoERG received minus nanoERG spent by the address */
+    pub balance_change: i64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressHistory {
+    /* Newest first */
+    pub transactions: Vec<AddressTransaction>,
+    pub total: u32,
+}
+
+#[cfg(feature = "server")]
+impl AddressDetails {
+    pub async fn api_fetch(address: &str) -> Result<AddressDetails, ServerFnError> {
+        let (balance, unspent_boxes, recent) = futures::join!(
+            api_fetch_balance(address),
+            api_fetch_unspent_boxes(address),
+            AddressHistory::api_fetch(address, 0, BALANCE_CHART_TRANSACTIONS),
+        );
+        let (confirmed, unconfirmed) = balance?;
+
+        Ok(AddressDetails {
+            address: address.to_string(),
+            balance_history: balance_history(confirmed.nano_ergs, &recent?.transactions),
+            confirmed,
+            unconfirmed,
+            unspent_boxes: unspent_boxes?,
+        })
+    }
+}
+
+#[cfg(feature = "server")]
+impl AddressHistory {
+    pub async fn api_fetch(
+        address: &str,
+        offset: u32,
+        limit: u32,
+    ) -> Result<AddressHistory, ServerFnError> {
+        let node_history: Value = node()
+            .get(&format!(
+                "{}{}?offset={}&limit={}",
+                NODE_TRANSACTIONS_BY_ADDRESS, address, offset, limit
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut transactions = vec![];
+        for node_transaction in node_history["items"].as_array().into_iter().flatten() {
+            let balance_change = balance_change(node_transaction, address);
+            let inclusion_height = node_transaction["inclusionHeight"]
+                .as_u64()
+                .unwrap_or_default() as u32;
+            let timestamp = node_transaction["timestamp"].as_u64().unwrap_or_default();
+
+            /* Indexed inputs carry their value, so no input lookups are needed */
+            if let Some(transaction) =
+                UnconfirmedTxs::from_node_values(vec![node_transaction.clone()])?.pop()
+            {
+                transactions.push(AddressTransaction {
+                    transaction,
+                    inclusion_height,
+                    timestamp,
+                    balance_change,
+                });
+            }
+        }
+        transactions.sort_by_key(|transaction| std::cmp::Reverse(transaction.inclusion_height));
+
+        Ok(AddressHistory {
+            transactions,
+            total: node_history["total"].as_u64().unwrap_or_default() as u32,
+        })
+    }
+}
+
+#[cfg(feature = "server")]
+async fn api_fetch_balance(address: &str) -> Result<(Balance, Balance), ServerFnError> {
+    let node_balance: Value = node()
+        .get(&format!("{}{}", NODE_BALANCE_FOR_ADDRESS, address))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let confirmed = serde_json::from_value(node_balance["confirmed"].clone()).unwrap_or_default();
+    let unconfirmed =
+        serde_json::from_value(node_balance["unconfirmed"].clone()).unwrap_or_default();
+    Ok((confirmed, unconfirmed))
+}
+
+#[cfg(feature = "server")]
+async fn api_fetch_unspent_boxes(address: &str) -> Result<Vec<Output>, ServerFnError> {
+    let boxes = node()
+        .get(&format!(
+            "{}{}?offset=0&limit={}&sortDirection=desc",
+            NODE_UNSPENT_BOXES_BY_ADDRESS, address, UNSPENT_BOXES_LIMIT
+        ))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(boxes)
+}
+
+/* Indexed inputs and outputs carry the address they belong to */
+#[cfg(feature = "server")]
+fn balance_change(node_transaction: &Value, address: &str) -> i64 {
+    let address_value = |boxes: &Value| -> i64 {
+        boxes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|node_box| node_box["address"].as_str() == Some(address))
+            .map(|node_box| node_box["value"].as_u64().unwrap_or_default() as i64)
+            .sum()
+    };
+    address_value(&node_transaction["outputs"]) - address_value(&node_transaction["inputs"])
+}
+
+/* Walk back from the current balance through the newest-first transactions */
+#[cfg(feature = "server")]
+fn balance_history(confirmed: u64, transactions: &[AddressTransaction]) -> Vec<BalancePoint> {
+    let mut nano_ergs = confirmed as i64;
+    let mut balance_history: Vec<BalancePoint> = transactions
+        .iter()
+        .map(|transaction| {
+            let point = BalancePoint {
+                height: transaction.inclusion_height,
+                timestamp: transaction.timestamp,
+                nano_ergs: nano_ergs.max(0) as u64,
+            };
+            nano_ergs -= transaction.balance_change;
+            point
+        })
+        .collect();
+    balance_history.reverse();
+    balance_history
+}
+
+/* None when the address is not a valid base58 Ergo address */
+#[server(GetAddressData)]
+pub async fn get_address_data(address: String) -> Result<Option<AddressDetails>, ServerFnError> {
+    if !is_valid_address(&address) {
+        return Ok(None);
+    }
+    Ok(Some(AddressDetails::api_fetch(&address).await?))
+}
+
+#[server(GetAddressHistory)]
+pub async fn get_address_history(
+    address: String,
+    page: u32,
+) -> Result<Option<AddressHistory>, ServerFnError> {
+    if !is_valid_address(&address) {
+        return Ok(None);
+    }
+    let history =
+        AddressHistory::api_fetch(&address, page * HISTORY_PAGE_SIZE, HISTORY_PAGE_SIZE).await?;
+    Ok(Some(history))
+}
@@ -0,0 +1,642 @@
+use axum::{
+    extract::{rejection::PathRejection, rejection::QueryRejection, Path, Query},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use dioxus::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::data::{
+    conflicts::{get_double_spends, DoubleSpend},
+    data::{
+        is_node_id, Asset, Block, BlockHeader, Extension, Input, Output, TransactionDetails,
+        UnconfirmedTxs,
+    },
+    dropped::{get_dropped_transactions, DroppedTransaction},
+    fees::{
+        api_fetch_max_block_size, fee_rate_histogram, FeeEstimate, FeeRateBucket, FeeStats,
+        TYPICAL_TRANSACTION_SIZE,
+    },
+    lifecycle::{TransactionLifecycle, TransactionOutcome},
+    poller::latest_snapshot,
+    token::annotate_assets,
+};
+
+/* Stable JSON API for bots, unlike the server functions' encodings. Bump the version, and the
+ * prefix with it, on any breaking change to the response bodies. */
+pub const API_PREFIX: &str = "/api/v1";
+const API_VERSION: u32 = 1;
+
+const DEFAULT_PAGE_LIMIT: usize = 100;
+const MAX_PAGE_LIMIT: usize = 1000;
+
+/* Every successful response */
+#[derive(Debug, Serialize)]
+struct ApiResponse<T> {
+    version: u32,
+    data: T,
+    /* Only for paginated lists */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiErrorBody {
+    version: u32,
+    error: ApiErrorDetails,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiErrorDetails {
+    /* Machine readable, one of invalid_request, not_found or node_error */
+    code: &'static str,
+    message: String,
+}
+
+/* Every failed response, rendered as an ApiErrorBody with the matching status */
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn invalid_request(message: impl ToString) -> ApiError {
+        ApiError {
+            status: StatusCode::BAD_REQUEST,
+            code: "invalid_request",
+            message: message.to_string(),
+        }
+    }
+
+    fn not_found(message: impl ToString) -> ApiError {
+        ApiError {
+            status: StatusCode::NOT_FOUND,
+            code: "not_found",
+            message: message.to_string(),
+        }
+    }
+}
+
+/* The data layer only fails when the node or the poller can't be reached */
+impl From<ServerFnError> for ApiError {
+    fn from(err: ServerFnError) -> ApiError {
+        ApiError {
+            status: StatusCode::BAD_GATEWAY,
+            code: "node_error",
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> ApiError {
+        ApiError::invalid_request(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> ApiError {
+        ApiError::invalid_request(rejection.body_text())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiErrorBody {
+            version: API_VERSION,
+            error: ApiErrorDetails {
+                code: self.code,
+                message: self.message,
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<ApiResponse<T>>, ApiError>;
+
+fn respond<T>(data: T) -> ApiResult<T> {
+    Ok(Json(ApiResponse {
+        version: API_VERSION,
+        data,
+        pagination: None,
+    }))
+}
+
+/* ?offset=&limit= of the paginated endpoints */
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Pagination {
+    offset: usize,
+    limit: usize,
+    /* Number of items in the whole list */
+    total: usize,
+}
+
+impl PageParams {
+    fn pagination(&self, total: usize) -> Result<Pagination, ApiError> {
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if limit == 0 || limit > MAX_PAGE_LIMIT {
+            return Err(ApiError::invalid_request(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_LIMIT
+            )));
+        }
+        Ok(Pagination {
+            offset: self.offset.unwrap_or_default(),
+            limit,
+            total,
+        })
+    }
+}
+
+impl Pagination {
+    fn page<T>(&self, items: Vec<T>) -> Vec<T> {
+        items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect()
+    }
+}
+
+/* The response bodies below copy the data layer types into snake_case fields, so the API
+ * keeps one naming convention whatever the node's camelCase formats and the server functions do */
+
+#[derive(Debug, Serialize)]
+struct ApiAsset {
+    token_id: String,
+    amount: u64,
+    /* From the token's EIP-4 metadata, when it has some */
+    name: Option<String>,
+    decimals: u32,
+}
+
+impl From<Asset> for ApiAsset {
+    fn from(asset: Asset) -> ApiAsset {
+        ApiAsset {
+            token_id: asset.token_id,
+            amount: asset.amount,
+            name: asset.name,
+            decimals: asset.decimals,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ApiInput {
+    box_id: String,
+    /* Null when the spent box couldn't be fetched */
+    value: Option<u64>,
+    proof_bytes: String,
+    extension: BTreeMap<String, String>,
+}
+
+impl From<Input> for ApiInput {
+    fn from(input: Input) -> ApiInput {
+        ApiInput {
+            box_id: input.box_id,
+            value: input.value,
+            proof_bytes: input.spending_proof.proof_bytes,
+            extension: input.spending_proof.extension,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ApiOutput {
+    box_id: String,
+    tx_id: String,
+    index: u32,
+    value: u64,
+    ergo_tree: String,
+    creation_height: u32,
+    assets: Vec<ApiAsset>,
+    /* Serialized constants by register name, R4 to R9 */
+    additional_registers: BTreeMap<String, String>,
+}
+
+impl From<Output> for ApiOutput {
+    fn from(output: Output) -> ApiOutput {
+        ApiOutput {
+            box_id: output.box_id,
+            tx_id: output.tx_id,
+            index: output.index,
+            value: output.value,
+            ergo_tree: output.ergo_tree,
+            creation_height: output.creation_height,
+            assets: output.assets.into_iter().map(ApiAsset::from).collect(),
+            additional_registers: output.additional_registers,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ApiTransaction {
+    id: String,
+    size: u32,
+    /* nanoERG paid to the miner fee contract, and per byte */
+    fee: u64,
+    fee_rate: f64,
+    inputs: Vec<ApiInput>,
+    data_input_box_ids: Vec<String>,
+    outputs: Vec<ApiOutput>,
+}
+
+impl From<UnconfirmedTxs> for ApiTransaction {
+    fn from(transaction: UnconfirmedTxs) -> ApiTransaction {
+        ApiTransaction {
+            fee: transaction.fee(),
+            fee_rate: transaction.fee_rate(),
+            id: transaction.id,
+            size: transaction.size,
+            inputs: transaction.inputs.into_iter().map(ApiInput::from).collect(),
+            data_input_box_ids: transaction
+                .data_inputs
+                .into_iter()
+                .map(|data_input| data_input.box_id)
+                .collect(),
+            outputs: transaction
+                .outputs
+                .into_iter()
+                .map(ApiOutput::from)
+                .collect(),
+        }
+    }
+}
+
+fn outcome_name(outcome: TransactionOutcome) -> &'static str {
+    match outcome {
+        TransactionOutcome::Confirmed => "confirmed",
+        TransactionOutcome::DoubleSpent => "double_spent",
+        TransactionOutcome::Dropped => "dropped",
+    }
+}
+
+/* Times in unix milliseconds */
+#[derive(Debug, Serialize)]
+struct ApiLifecycle {
+    first_seen: u64,
+    last_seen: u64,
+    left_at: Option<u64>,
+    /* confirmed, double_spent or dropped once the transaction left the mempool */
+    outcome: Option<&'static str>,
+    confirmed_height: Option<u32>,
+    confirmed_at: Option<u64>,
+}
+
+impl From<TransactionLifecycle> for ApiLifecycle {
+    fn from(lifecycle: TransactionLifecycle) -> ApiLifecycle {
+        ApiLifecycle {
+            first_seen: lifecycle.first_seen,
+            last_seen: lifecycle.last_seen,
+            left_at: lifecycle.left_at,
+            outcome: lifecycle.outcome.map(outcome_name),
+            confirmed_height: lifecycle.confirmed_height,
+            confirmed_at: lifecycle.confirmed_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TransactionResponse {
+    transaction: ApiTransaction,
+    /* Inclusion fields are null while the transaction is still in the mempool */
+    inclusion_height: Option<u32>,
+    block_id: Option<String>,
+    timestamp: Option<u64>,
+    confirmations: u32,
+    double_spends: Vec<DoubleSpend>,
+    /* Null when this server never saw the transaction in the mempool */
+    lifecycle: Option<ApiLifecycle>,
+}
+
+impl From<TransactionDetails> for TransactionResponse {
+    fn from(details: TransactionDetails) -> TransactionResponse {
+        TransactionResponse {
+            transaction: details.transaction.into(),
+            inclusion_height: details.inclusion_height,
+            block_id: details.block_id,
+            timestamp: details.timestamp,
+            confirmations: details.confirmations,
+            double_spends: details.double_spends,
+            lifecycle: details.lifecycle.map(ApiLifecycle::from),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BlockResponse {
+    header: BlockHeader,
+    /* One page of the block's transactions */
+    transactions: Vec<ApiTransaction>,
+    extension: Extension,
+    ad_proofs_size: u32,
+    size: u32,
+}
+
+impl From<Block> for BlockResponse {
+    fn from(block: Block) -> BlockResponse {
+        BlockResponse {
+            header: block.header,
+            transactions: block
+                .transactions
+                .into_iter()
+                .map(ApiTransaction::from)
+                .collect(),
+            extension: block.extension,
+            ad_proofs_size: block.ad_proofs_size,
+            size: block.size,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ApiDroppedTransaction {
+    tx_id: String,
+    size: u32,
+    fee: u64,
+    fee_rate: f64,
+    last_seen: u64,
+    left_at: u64,
+    /* double_spent or dropped */
+    outcome: &'static str,
+}
+
+impl From<DroppedTransaction> for ApiDroppedTransaction {
+    fn from(transaction: DroppedTransaction) -> ApiDroppedTransaction {
+        ApiDroppedTransaction {
+            tx_id: transaction.tx_id,
+            size: transaction.size,
+            fee: transaction.fee,
+            fee_rate: transaction.fee_rate,
+            last_seen: transaction.last_seen,
+            left_at: transaction.left_at,
+            outcome: outcome_name(transaction.outcome),
+        }
+    }
+}
+
+/* Mempool summary with one page of its transactions */
+#[derive(Debug, Serialize)]
+struct MempoolResponse {
+    mempool_size: u32,
+    total_size: u64,
+    total_value: u64,
+    snapshot_time: u64,
+    /* Highest fee rate first */
+    transactions: Vec<ApiTransaction>,
+}
+
+#[derive(Debug, Serialize)]
+struct FeesResponse {
+    stats: FeeStats,
+    estimate: FeeEstimate,
+    histogram: Vec<FeeRateBucket>,
+}
+
+async fn mempool(params: Result<Query<PageParams>, QueryRejection>) -> ApiResult<MempoolResponse> {
+    let Query(params) = params?;
+    let snapshot = latest_snapshot().await?;
+    let pagination = params.pagination(snapshot.unconfirmed_txs.len())?;
+
+    let mut transactions = snapshot.unconfirmed_txs.clone();
+    transactions.sort_by(|a, b| b.fee_rate().total_cmp(&a.fee_rate()));
+
+    Ok(Json(ApiResponse {
+        version: API_VERSION,
+        data: MempoolResponse {
+            mempool_size: snapshot.mempool_size,
+            total_size: snapshot.total_size,
+            total_value: snapshot.total_value,
+            snapshot_time: snapshot.snapshot_time,
+            transactions: pagination
+                .page(transactions)
+                .into_iter()
+                .map(ApiTransaction::from)
+                .collect(),
+        },
+        pagination: Some(pagination),
+    }))
+}
+
+async fn transaction(tx_id: Result<Path<String>, PathRejection>) -> ApiResult<TransactionResponse> {
+    let Path(tx_id) = tx_id?;
+    if !is_node_id(&tx_id) {
+        return Err(ApiError::invalid_request(format!(
+            "{} is not a 64 character hex transaction id",
+            tx_id
+        )));
+    }
+    match TransactionDetails::api_fetch(&tx_id).await? {
+        Some(details) => respond(details.into()),
+        None => Err(ApiError::not_found(format!(
+            "transaction {} not found",
+            tx_id
+        ))),
+    }
+}
+
+/* The block on the node's best chain, with one page of its transactions */
+async fn block(
+    height: Result<Path<u32>, PathRejection>,
+    params: Result<Query<PageParams>, QueryRejection>,
+) -> ApiResult<BlockResponse> {
+    let Path(height) = height?;
+    let Query(params) = params?;
+    let Some(block_id) = Block::api_fetch_block_ids(height).await?.into_iter().next() else {
+        return Err(ApiError::not_found(format!(
+            "no block at height {}",
+            height
+        )));
+    };
+
+    let mut block = Block::api_fetch_block(&block_id).await?;
+    let pagination = params.pagination(block.transactions.len())?;
+    block.transactions = pagination.page(block.transactions);
+    UnconfirmedTxs::api_fetch_input_values(&mut block.transactions).await;
+    annotate_assets(&mut block.transactions).await;
+
+    Ok(Json(ApiResponse {
+        version: API_VERSION,
+        data: block.into(),
+        pagination: Some(pagination),
+    }))
+}
+
+/* Stats, estimate and histogram all come from the same snapshot */
+async fn fees() -> ApiResult<FeesResponse> {
+    let snapshot = latest_snapshot().await?;
+    let max_block_size = api_fetch_max_block_size().await?;
+    respond(FeesResponse {
+        stats: FeeStats::from_transactions(&snapshot.unconfirmed_txs, snapshot.snapshot_time),
+        estimate: FeeEstimate::from_transactions(
+            &snapshot.unconfirmed_txs,
+            TYPICAL_TRANSACTION_SIZE,
+            max_block_size,
+            snapshot.snapshot_time,
+        ),
+        histogram: fee_rate_histogram(&snapshot.unconfirmed_txs),
+    })
+}
+
+/* Tracked double spends, newest first */
+async fn double_spends() -> ApiResult<Vec<DoubleSpend>> {
+    respond(get_double_spends().await?)
+}
+
+/* Transactions that recently left the mempool without being confirmed, newest first */
+async fn dropped() -> ApiResult<Vec<ApiDroppedTransaction>> {
+    let dropped = get_dropped_transactions().await?;
+    respond(
+        dropped
+            .into_iter()
+            .map(ApiDroppedTransaction::from)
+            .collect(),
+    )
+}
+
+async fn not_found() -> ApiError {
+    ApiError::not_found("no such endpoint")
+}
+
+/* Nested under API_PREFIX by the server */
+pub fn router() -> Router {
+    Router::new()
+        .route("/mempool", get(mempool))
+        .route("/tx/:tx_id", get(transaction))
+        .route("/block/:height", get(block))
+        .route("/fees", get(fees))
+        .route("/double-spends", get(double_spends))
+        .route("/dropped", get(dropped))
+        .fallback(not_found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use serde_json::{json, Value};
+
+    fn page_params(offset: Option<usize>, limit: Option<usize>) -> PageParams {
+        PageParams { offset, limit }
+    }
+
+    fn body(response: Response) -> Value {
+        let bytes = block_on(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn bounds_page_limits() {
+        for limit in [0, MAX_PAGE_LIMIT + 1] {
+            let err = page_params(None, Some(limit)).pagination(10).unwrap_err();
+            assert_eq!(
+                (err.status, err.code),
+                (StatusCode::BAD_REQUEST, "invalid_request")
+            );
+        }
+
+        let pagination = page_params(None, None).pagination(10).unwrap();
+        assert_eq!(
+            (pagination.offset, pagination.limit),
+            (0, DEFAULT_PAGE_LIMIT)
+        );
+        let pagination = page_params(Some(2), Some(MAX_PAGE_LIMIT))
+            .pagination(5)
+            .unwrap();
+        assert_eq!(pagination.page(vec![0, 1, 2, 3, 4]), vec![2, 3, 4]);
+        let pagination = page_params(Some(1), Some(2)).pagination(5).unwrap();
+        assert_eq!(pagination.page(vec![0, 1, 2, 3, 4]), vec![1, 2]);
+        let pagination = page_params(Some(10), None).pagination(5).unwrap();
+        assert!(pagination.page(vec![0, 1, 2, 3, 4]).is_empty());
+        assert_eq!(pagination.total, 5);
+    }
+
+    #[test]
+    fn rejects_malformed_transaction_ids() {
+        for tx_id in ["abc", &"g".repeat(64), &"a".repeat(65)] {
+            let err = block_on(transaction(Ok(Path(tx_id.to_string())))).unwrap_err();
+            assert_eq!(
+                (err.status, err.code),
+                (StatusCode::BAD_REQUEST, "invalid_request")
+            );
+        }
+    }
+
+    #[test]
+    fn answers_unknown_endpoints_with_an_error_body() {
+        let response = block_on(not_found()).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body(response),
+            json!({
+                "version": API_VERSION,
+                "error": { "code": "not_found", "message": "no such endpoint" },
+            })
+        );
+    }
+
+    /* Every key of the value and of the objects nested in it */
+    fn keys(value: &Value, found: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    found.push(key.clone());
+                    keys(value, found);
+                }
+            }
+            Value::Array(array) => array.iter().for_each(|value| keys(value, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn serializes_transactions_in_snake_case() {
+        let mut transaction = UnconfirmedTxs::for_test("a", &["box"], 1_000_000, 200);
+        transaction.outputs[0].assets.push(Asset {
+            token_id: "token".to_string(),
+            amount: 1,
+            ..Asset::default()
+        });
+        transaction.outputs[0]
+            .additional_registers
+            .insert("R4".to_string(), "0e0132".to_string());
+        let response = TransactionResponse::from(TransactionDetails {
+            transaction,
+            lifecycle: Some(TransactionLifecycle {
+                outcome: Some(TransactionOutcome::DoubleSpent),
+                ..TransactionLifecycle::default()
+            }),
+            ..TransactionDetails::default()
+        });
+        let value = serde_json::to_value(&response).unwrap();
+
+        let mut found = vec![];
+        keys(&value, &mut found);
+        for key in found.iter().filter(|key| key.as_str() != "R4") {
+            assert_eq!(key, &key.to_lowercase(), "{} is not snake_case", key);
+        }
+        for key in [
+            "data_input_box_ids",
+            "additional_registers",
+            "box_id",
+            "token_id",
+            "fee_rate",
+        ] {
+            assert!(found.iter().any(|found| found == key), "missing {}", key);
+        }
+        assert_eq!(value["transaction"]["inputs"][0]["value"], Value::Null);
+        assert_eq!(value["lifecycle"]["outcome"], "double_spent");
+    }
+}
@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::data::{config::node, data::Block};
+#[cfg(feature = "server")]
+use serde_json::Value;
+#[cfg(feature = "server")]
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+#[cfg(feature = "server")]
+const NODE_BLOCKS_LAST_HEADERS: &str = "/blocks/lastHeaders/";
+
+/* EIP-27 re-emission token on mainnet. The coinbase hands the miner one per re-emitted
+ * nanoERG, and spending the reward box means paying as many to the re-emission contract. */
+#[cfg(feature = "server")]
+const REEMISSION_TOKEN_ID: &str =
+    "d9a2cc8a09abfaed87afacfbb7daee79a6b26f10c6613fc13d3f3953e5521d1a";
+
+/* Number of blocks shown in the homepage strip */
+#[cfg(feature = "server")]
+const RECENT_BLOCKS: u32 = 8;
+
+/* What the homepage strip shows of a confirmed block */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockSummary {
+    pub height: u32,
+    pub id: String,
+    pub timestamp: u64,
+    pub transaction_count: u32,
+    pub size: u32,
+    /* nanoERG of the block emission the miner keeps: the coinbase reward output less the
+     * EIP-27 re-emission it must pay back. Fees are excluded. */
+    pub miner_reward: u64,
+    pub total_fees: u64,
+}
+
+#[cfg(feature = "server")]
+impl BlockSummary {
+    fn from_block(block: &Block) -> BlockSummary {
+        /* The coinbase transaction spends the emission box, its first output is the new
+         * emission box and the second one the miner's reward */
+        let miner_reward = block
+            .transactions
+            .first()
+            .and_then(|coinbase| coinbase.outputs.get(1))
+            .map(|reward| {
+                let reemission: u64 = reward
+                    .assets
+                    .iter()
+                    .filter(|asset| asset.token_id == REEMISSION_TOKEN_ID)
+                    .map(|asset| asset.amount)
+                    .sum();
+                reward.value.saturating_sub(reemission)
+            })
+            .unwrap_or_default();
+
+        BlockSummary {
+            height: block.header.height,
+            id: block.header.id.clone(),
+            timestamp: block.header.timestamp,
+            transaction_count: block.transactions.len() as u32,
+            size: block.size,
+            miner_reward,
+            total_fees: block
+                .transactions
+                .iter()
+                .map(|transaction| transaction.fee())
+                .sum(),
+        }
+    }
+}
+
+/* Blocks never change once fetched, summaries are kept for the RECENT_BLOCKS latest ids */
+#[cfg(feature = "server")]
+fn block_summaries() -> &'static Mutex<HashMap<String, BlockSummary>> {
+    static BLOCK_SUMMARIES: OnceLock<Mutex<HashMap<String, BlockSummary>>> = OnceLock::new();
+    BLOCK_SUMMARIES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/* Newest first */
+#[server(GetRecentBlocks)]
+pub async fn get_recent_blocks() -> Result<Vec<BlockSummary>, ServerFnError> {
+    let headers: Vec<Value> = node()
+        .get(&format!("{}{}", NODE_BLOCKS_LAST_HEADERS, RECENT_BLOCKS))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let block_ids: Vec<String> = headers
+        .iter()
+        .rev()
+        .filter_map(|header| header["id"].as_str().map(str::to_string))
+        .collect();
+
+    let mut summaries = vec![];
+    for block_id in block_ids.iter() {
+        let cached = block_summaries().lock().unwrap().get(block_id).cloned();
+        let summary = match cached {
+            Some(summary) => summary,
+            None => BlockSummary::from_block(&Block::api_fetch_block(block_id).await?),
+        };
+        summaries.push(summary);
+    }
+
+    let mut cache = block_summaries().lock().unwrap();
+    cache.clear();
+    cache.extend(
+        summaries
+            .iter()
+            .map(|summary| (summary.id.clone(), summary.clone())),
+    );
+    Ok(summaries)
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use crate::data::data::{Asset, Output, UnconfirmedTxs};
+
+    fn block_with_coinbase(outputs: Vec<Output>) -> Block {
+        Block {
+            transactions: vec![UnconfirmedTxs {
+                outputs,
+                ..UnconfirmedTxs::default()
+            }],
+            ..Block::default()
+        }
+    }
+
+    fn output(value: u64, assets: Vec<Asset>) -> Output {
+        Output {
+            value,
+            assets,
+            ..Output::default()
+        }
+    }
+
+    #[test]
+    fn subtracts_the_reemission_from_the_miner_reward() {
+        let reemission = Asset {
+            token_id: REEMISSION_TOKEN_ID.to_string(),
+            amount: 12_000_000_000,
+            ..Asset::default()
+        };
+        let block = block_with_coinbase(vec![
+            output(1_000_000_000_000, vec![]),
+            output(15_000_000_000, vec![reemission]),
+        ]);
+        assert_eq!(BlockSummary::from_block(&block).miner_reward, 3_000_000_000);
+    }
+
+    #[test]
+    fn keeps_the_whole_reward_before_reemission() {
+        let block = block_with_coinbase(vec![
+            output(1_000_000_000_000, vec![]),
+            output(67_500_000_000, vec![]),
+        ]);
+        assert_eq!(
+            BlockSummary::from_block(&block).miner_reward,
+            67_500_000_000
+        );
+    }
+
+    #[test]
+    fn ignores_coinbase_outputs_past_the_reward() {
+        let block = block_with_coinbase(vec![
+            output(1_000_000_000_000, vec![]),
+            output(3_000_000_000, vec![]),
+            output(12_000_000_000, vec![]),
+        ]);
+        assert_eq!(BlockSummary::from_block(&block).miner_reward, 3_000_000_000);
+        assert_eq!(BlockSummary::from_block(&Block::default()).miner_reward, 0);
+    }
+}
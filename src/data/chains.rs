@@ -0,0 +1,275 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::data::data::UnconfirmedTxs;
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+
+/* A mempool transaction seen as part of a chain, rates in nanoERG per byte */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainTransaction {
+    pub tx_id: String,
+    pub size: u32,
+    pub fee: u64,
+    pub fee_rate: f64,
+    /* Fee rate of the transaction together with all its unconfirmed ancestors */
+    pub package_fee_rate: f64,
+    /* Length of the longest path from a transaction without mempool parents */
+    pub depth: u32,
+    pub ancestors: Vec<String>,
+    pub descendants: Vec<String>,
+}
+
+/* The child spends at least one output of the parent */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainLink {
+    pub parent: String,
+    pub child: String,
+}
+
+/* Mempool transactions connected through spent outputs, parents before children */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionChain {
+    pub transactions: Vec<ChainTransaction>,
+    pub links: Vec<ChainLink>,
+}
+
+/* Spending relations between the transactions of a snapshot, by index into it */
+#[cfg(feature = "server")]
+pub struct MempoolGraph {
+    pub parents: Vec<Vec<usize>>,
+    pub children: Vec<Vec<usize>>,
+}
+
+#[cfg(feature = "server")]
+impl MempoolGraph {
+    pub fn new(transactions: &[UnconfirmedTxs]) -> MempoolGraph {
+        let creators: HashMap<&str, usize> = transactions
+            .iter()
+            .enumerate()
+            .flat_map(|(index, transaction)| {
+                transaction
+                    .outputs
+                    .iter()
+                    .map(move |output| (output.box_id.as_str(), index))
+            })
+            .collect();
+
+        let mut parents: Vec<Vec<usize>> = vec![vec![]; transactions.len()];
+        let mut children: Vec<Vec<usize>> = vec![vec![]; transactions.len()];
+        for (index, transaction) in transactions.iter().enumerate() {
+            let mut spent_from: Vec<usize> = transaction
+                .inputs
+                .iter()
+                .filter_map(|input| creators.get(input.box_id.as_str()).copied())
+                .filter(|&parent| parent != index)
+                .collect();
+            spent_from.sort_unstable();
+            spent_from.dedup();
+
+            for &parent in spent_from.iter() {
+                children[parent].push(index);
+            }
+            parents[index] = spent_from;
+        }
+
+        MempoolGraph { parents, children }
+    }
+
+    /* Every transaction reachable through `edges`, excluding the start */
+    fn reachable(edges: &[Vec<usize>], start: usize) -> Vec<usize> {
+        let mut seen = vec![false; edges.len()];
+        let mut pending = edges[start].clone();
+        let mut reached = vec![];
+        while let Some(index) = pending.pop() {
+            if index == start || std::mem::replace(&mut seen[index], true) {
+                continue;
+            }
+            reached.push(index);
+            pending.extend(edges[index].iter().copied());
+        }
+        reached.sort_unstable();
+        reached
+    }
+
+    pub fn ancestors(&self, index: usize) -> Vec<usize> {
+        MempoolGraph::reachable(&self.parents, index)
+    }
+
+    pub fn descendants(&self, index: usize) -> Vec<usize> {
+        MempoolGraph::reachable(&self.children, index)
+    }
+
+    pub fn package_fee_rate(&self, transactions: &[UnconfirmedTxs], index: usize) -> f64 {
+        let package: Vec<&UnconfirmedTxs> = self
+            .ancestors(index)
+            .into_iter()
+            .chain([index])
+            .map(|member| &transactions[member])
+            .collect();
+        let size: u64 = package.iter().map(|member| member.size as u64).sum();
+        let fee: u64 = package.iter().map(|member| member.fee()).sum();
+        match size {
+            0 => 0.0,
+            size => fee as f64 / size as f64,
+        }
+    }
+
+    /* Depth of every transaction, a cycle (which the node would reject) ends the walk */
+    pub fn depths(&self) -> Vec<u32> {
+        let mut depths = vec![0u32; self.parents.len()];
+        let mut missing_parents: Vec<usize> = self.parents.iter().map(Vec::len).collect();
+        let mut ready: Vec<usize> = (0..self.parents.len())
+            .filter(|&index| missing_parents[index] == 0)
+            .collect();
+        while let Some(index) = ready.pop() {
+            for &child in self.children[index].iter() {
+                depths[child] = depths[child].max(depths[index] + 1);
+                missing_parents[child] -= 1;
+                if missing_parents[child] == 0 {
+                    ready.push(child);
+                }
+            }
+        }
+        depths
+    }
+
+    /* Connected groups of at least two transactions, largest first */
+    pub fn chains(&self, transactions: &[UnconfirmedTxs]) -> Vec<TransactionChain> {
+        let depths = self.depths();
+        let tx_ids = |indexes: Vec<usize>| -> Vec<String> {
+            indexes
+                .into_iter()
+                .map(|index| transactions[index].id.clone())
+                .collect()
+        };
+
+        let mut visited = vec![false; transactions.len()];
+        let mut chains = vec![];
+        for start in 0..transactions.len() {
+            if visited[start] || (self.parents[start].is_empty() && self.children[start].is_empty())
+            {
+                continue;
+            }
+
+            let mut members = vec![];
+            let mut pending = vec![start];
+            while let Some(index) = pending.pop() {
+                if std::mem::replace(&mut visited[index], true) {
+                    continue;
+                }
+                members.push(index);
+                pending.extend(self.parents[index].iter().copied());
+                pending.extend(self.children[index].iter().copied());
+            }
+            members.sort_by_key(|&index| (depths[index], index));
+
+            chains.push(TransactionChain {
+                transactions: members
+                    .iter()
+                    .map(|&index| {
+                        let transaction = &transactions[index];
+                        ChainTransaction {
+                            tx_id: transaction.id.clone(),
+                            size: transaction.size,
+                            fee: transaction.fee(),
+                            fee_rate: transaction.fee_rate(),
+                            package_fee_rate: self.package_fee_rate(transactions, index),
+                            depth: depths[index],
+                            ancestors: tx_ids(self.ancestors(index)),
+                            descendants: tx_ids(self.descendants(index)),
+                        }
+                    })
+                    .collect(),
+                links: members
+                    .iter()
+                    .flat_map(|&index| {
+                        self.children[index].iter().map(move |&child| ChainLink {
+                            parent: transactions[index].id.clone(),
+                            child: transactions[child].id.clone(),
+                        })
+                    })
+                    .collect(),
+            });
+        }
+        chains.sort_by_key(|chain| std::cmp::Reverse(chain.transactions.len()));
+        chains
+    }
+}
+
+#[server(GetMempoolChains)]
+pub async fn get_mempool_chains() -> Result<Vec<TransactionChain>, ServerFnError> {
+    let data = crate::data::poller::latest_snapshot().await?;
+    Ok(MempoolGraph::new(&data.unconfirmed_txs).chains(&data.unconfirmed_txs))
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    /* root -> (left, right) -> grandchild, spending both, plus an unrelated transaction */
+    fn diamond() -> Vec<UnconfirmedTxs> {
+        vec![
+            UnconfirmedTxs::for_test("grandchild", &["left:0", "right:0"], 4_000_000, 1000),
+            UnconfirmedTxs::for_test("root", &["confirmed"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("left", &["root:0"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("right", &["root:0", "other"], 2_000_000, 1000),
+            UnconfirmedTxs::for_test("alone", &["elsewhere"], 1_000_000, 1000),
+        ]
+    }
+
+    #[test]
+    fn links_parents_and_children_through_spent_outputs() {
+        let graph = MempoolGraph::new(&diamond());
+        assert_eq!(
+            graph.parents,
+            [vec![2, 3], vec![], vec![1], vec![1], vec![]]
+        );
+        assert_eq!(
+            graph.children,
+            [vec![], vec![2, 3], vec![0], vec![0], vec![]]
+        );
+        assert_eq!(graph.ancestors(0), [1, 2, 3]);
+        assert_eq!(graph.descendants(1), [0, 2, 3]);
+        assert_eq!(graph.depths(), [2, 0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn counts_each_parent_once_and_ignores_self_references() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("parent", &["a"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("child", &["parent:0", "parent:1"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("looping", &["looping:0"], 1_000_000, 1000),
+        ];
+        let graph = MempoolGraph::new(&transactions);
+        assert_eq!(graph.parents, [vec![], vec![0], vec![]]);
+        assert_eq!(graph.children, [vec![1], vec![], vec![]]);
+    }
+
+    #[test]
+    fn package_fee_rate_includes_every_ancestor() {
+        let transactions = diamond();
+        let graph = MempoolGraph::new(&transactions);
+        assert_eq!(graph.package_fee_rate(&transactions, 1), 1000.0);
+        assert_eq!(graph.package_fee_rate(&transactions, 3), 1500.0);
+        assert_eq!(graph.package_fee_rate(&transactions, 0), 2000.0);
+    }
+
+    #[test]
+    fn chains_leave_out_unconnected_transactions() {
+        let transactions = diamond();
+        let chains = MempoolGraph::new(&transactions).chains(&transactions);
+        assert_eq!(chains.len(), 1);
+
+        let chain = &chains[0];
+        let tx_ids: Vec<&str> = chain
+            .transactions
+            .iter()
+            .map(|transaction| transaction.tx_id.as_str())
+            .collect();
+        assert_eq!(tx_ids, ["root", "left", "right", "grandchild"]);
+        assert_eq!(chain.links.len(), 4);
+        assert_eq!(chain.transactions[3].ancestors.len(), 3);
+    }
+}
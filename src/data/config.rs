@@ -0,0 +1,221 @@
+use std::{env, fs, io, sync::OnceLock, time::Duration};
+
+use dioxus_logger::tracing;
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client, RequestBuilder,
+};
+use serde::Deserialize;
+
+/* Config file read at server start-up, overridable with ERGOVISUAL_CONFIG */
+const CONFIG_FILE: &str = "ergovisual.toml";
+
+/* Header used by the Ergo node for authenticated endpoints */
+const API_KEY_HEADER: &str = "api_key";
+
+static NODE: OnceLock<Node> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct NodeConfig {
+    /* Base URL of the node REST API, without a trailing slash */
+    pub url: String,
+    pub api_key: Option<String>,
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    /* Number of transactions requested per /transactions/unconfirmed call */
+    pub page_size: u32,
+    /* Stop paging through the mempool once this many transactions have been fetched */
+    pub max_unconfirmed_transactions: u32,
+    /* Interval between two mempool snapshots taken by the background poller */
+    pub poll_interval_secs: u64,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        NodeConfig {
+            url: "http://api.sigmamining.xyz".to_string(),
+            api_key: None,
+            timeout_secs: 30,
+            connect_timeout_secs: 5,
+            page_size: 100,
+            max_unconfirmed_transactions: 5000,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /* SQLite database file, created on first start */
+    pub path: String,
+    /* Snapshots, transactions and blocks older than this are deleted, 0 keeps everything */
+    pub retention_days: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            path: "ergovisual.db".to_string(),
+            retention_days: 30,
+        }
+    }
+}
+
+/* Every server setting, read once at start-up */
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    pub node: NodeConfig,
+    pub storage: StorageConfig,
+}
+
+impl ConfigFile {
+    /* Read the config file (if present) and apply the environment overrides of every section */
+    pub fn load() -> Result<ConfigFile, String> {
+        let mut config = ConfigFile::read()?;
+        config.node.apply_env()?;
+        config.storage.apply_env()?;
+        Ok(config)
+    }
+
+    /* Only a missing default file falls back to the defaults, an explicit ERGOVISUAL_CONFIG
+     * must be readable */
+    fn read() -> Result<ConfigFile, String> {
+        let explicit_path = env::var("ERGOVISUAL_CONFIG").ok();
+        let path = explicit_path
+            .clone()
+            .unwrap_or_else(|| CONFIG_FILE.to_string());
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str::<ConfigFile>(&contents)
+                .map_err(|err| format!("invalid config file {}: {}", path, err)),
+            Err(err) if explicit_path.is_none() && err.kind() == io::ErrorKind::NotFound => {
+                tracing::info!("No config file at {}, using defaults", path);
+                Ok(ConfigFile::default())
+            }
+            Err(err) => Err(format!("can't read config file {}: {}", path, err)),
+        }
+    }
+}
+
+impl NodeConfig {
+    /* Apply ERGOVISUAL_NODE_* environment overrides */
+    fn apply_env(&mut self) -> Result<(), String> {
+        if let Ok(url) = env::var("ERGOVISUAL_NODE_URL") {
+            self.url = url;
+        }
+        if let Ok(api_key) = env::var("ERGOVISUAL_NODE_API_KEY") {
+            self.api_key = Some(api_key);
+        }
+        if let Ok(timeout_secs) = env::var("ERGOVISUAL_NODE_TIMEOUT_SECS") {
+            self.timeout_secs = parse_env("ERGOVISUAL_NODE_TIMEOUT_SECS", &timeout_secs)?;
+        }
+        if let Ok(connect_timeout_secs) = env::var("ERGOVISUAL_NODE_CONNECT_TIMEOUT_SECS") {
+            self.connect_timeout_secs = parse_env(
+                "ERGOVISUAL_NODE_CONNECT_TIMEOUT_SECS",
+                &connect_timeout_secs,
+            )?;
+        }
+        if let Ok(page_size) = env::var("ERGOVISUAL_NODE_PAGE_SIZE") {
+            self.page_size = parse_env("ERGOVISUAL_NODE_PAGE_SIZE", &page_size)?;
+        }
+        if let Ok(max_unconfirmed_transactions) =
+            env::var("ERGOVISUAL_NODE_MAX_UNCONFIRMED_TRANSACTIONS")
+        {
+            self.max_unconfirmed_transactions = parse_env(
+                "ERGOVISUAL_NODE_MAX_UNCONFIRMED_TRANSACTIONS",
+                &max_unconfirmed_transactions,
+            )?;
+        }
+        if let Ok(poll_interval_secs) = env::var("ERGOVISUAL_NODE_POLL_INTERVAL_SECS") {
+            self.poll_interval_secs =
+                parse_env("ERGOVISUAL_NODE_POLL_INTERVAL_SECS", &poll_interval_secs)?;
+        }
+
+        self.url = self.url.trim_end_matches('/').to_string();
+        if self.page_size == 0
+            || self.max_unconfirmed_transactions == 0
+            || self.poll_interval_secs == 0
+        {
+            return Err(
+                "node page_size, max_unconfirmed_transactions and poll_interval_secs must be greater than 0"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl StorageConfig {
+    /* Apply ERGOVISUAL_STORAGE_* environment overrides */
+    fn apply_env(&mut self) -> Result<(), String> {
+        if let Ok(path) = env::var("ERGOVISUAL_STORAGE_PATH") {
+            self.path = path;
+        }
+        if let Ok(retention_days) = env::var("ERGOVISUAL_STORAGE_RETENTION_DAYS") {
+            self.retention_days = parse_env("ERGOVISUAL_STORAGE_RETENTION_DAYS", &retention_days)?;
+        }
+
+        if self.path.is_empty() {
+            return Err("storage path must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("invalid value for {}: {}", name, value))
+}
+
+/* The node connection shared by every server function */
+#[derive(Debug)]
+pub struct Node {
+    pub config: NodeConfig,
+    client: Client,
+}
+
+impl Node {
+    fn new(config: NodeConfig) -> Result<Node, String> {
+        let mut headers = HeaderMap::new();
+        if let Some(api_key) = &config.api_key {
+            let api_key =
+                HeaderValue::from_str(api_key).map_err(|_| "invalid node api_key".to_string())?;
+            headers.insert(API_KEY_HEADER, api_key);
+        }
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+            .build()
+            .map_err(|err| format!("failed to build node client: {}", err))?;
+
+        Ok(Node { config, client })
+    }
+
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.client.get(format!("{}{}", self.config.url, path))
+    }
+
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.client.post(format!("{}{}", self.config.url, path))
+    }
+}
+
+/* Build the node client, called once at server start-up */
+pub fn init(config: NodeConfig) -> Result<&'static Node, String> {
+    if let Some(node) = NODE.get() {
+        return Ok(node);
+    }
+    let node = Node::new(config)?;
+    tracing::info!("Using Ergo node at {}", node.config.url);
+    Ok(NODE.get_or_init(|| node))
+}
+
+pub fn node() -> &'static Node {
+    NODE.get().expect("node used before config::init")
+}
@@ -0,0 +1,181 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::data::{data::UnconfirmedTxs, poller::poller};
+#[cfg(feature = "server")]
+use std::collections::BTreeMap;
+
+/* Resolved double spends kept for the API once the conflict left the mempool */
+#[cfg(feature = "server")]
+const MAX_RESOLVED_DOUBLE_SPENDS: usize = 100;
+
+/* A box spent by more than one unconfirmed transaction, times in unix milliseconds */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoubleSpend {
+    pub box_id: String,
+    /* Every transaction seen spending the box while the conflict lasted */
+    pub tx_ids: Vec<String>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    /* False once at most one of the transactions is left in the mempool */
+    pub active: bool,
+}
+
+/* Boxes spent by more than one transaction of the snapshot, with the spending transactions */
+#[cfg(feature = "server")]
+fn conflicting_inputs(transactions: &[UnconfirmedTxs]) -> BTreeMap<&str, Vec<&str>> {
+    let mut spenders: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for transaction in transactions {
+        for input in transaction.inputs.iter() {
+            let tx_ids = spenders.entry(input.box_id.as_str()).or_default();
+            if !tx_ids.contains(&transaction.id.as_str()) {
+                tx_ids.push(transaction.id.as_str());
+            }
+        }
+    }
+    spenders.retain(|_, tx_ids| tx_ids.len() > 1);
+    spenders
+}
+
+/* Fold the conflicts of a new snapshot into the tracked ones, newest first */
+#[cfg(feature = "server")]
+pub fn track_double_spends(
+    tracked: &mut Vec<DoubleSpend>,
+    transactions: &[UnconfirmedTxs],
+    snapshot_time: u64,
+) {
+    let mut conflicts = conflicting_inputs(transactions);
+
+    for double_spend in tracked
+        .iter_mut()
+        .filter(|double_spend| double_spend.active)
+    {
+        match conflicts.remove(double_spend.box_id.as_str()) {
+            Some(tx_ids) => {
+                for tx_id in tx_ids {
+                    if !double_spend.tx_ids.iter().any(|known| known == tx_id) {
+                        double_spend.tx_ids.push(tx_id.to_string());
+                    }
+                }
+                double_spend.last_seen = snapshot_time;
+            }
+            None => double_spend.active = false,
+        }
+    }
+
+    let new: Vec<DoubleSpend> = conflicts
+        .into_iter()
+        .map(|(box_id, tx_ids)| DoubleSpend {
+            box_id: box_id.to_string(),
+            tx_ids: tx_ids.into_iter().map(str::to_string).collect(),
+            first_seen: snapshot_time,
+            last_seen: snapshot_time,
+            active: true,
+        })
+        .collect();
+    tracked.splice(0..0, new);
+
+    let mut resolved = 0;
+    tracked.retain(|double_spend| {
+        if !double_spend.active {
+            resolved += 1;
+        }
+        double_spend.active || resolved <= MAX_RESOLVED_DOUBLE_SPENDS
+    });
+}
+
+/* Active double spends involving the transaction */
+#[cfg(feature = "server")]
+pub fn double_spends_of(tx_id: &str) -> Vec<DoubleSpend> {
+    poller()
+        .borrow()
+        .double_spends
+        .iter()
+        .filter(|double_spend| {
+            double_spend.active && double_spend.tx_ids.iter().any(|known| known == tx_id)
+        })
+        .cloned()
+        .collect()
+}
+
+#[server(GetDoubleSpends)]
+pub async fn get_double_spends() -> Result<Vec<DoubleSpend>, ServerFnError> {
+    Ok(poller().borrow().double_spends.clone())
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_boxes_spent_by_several_transactions() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("a", &["box", "own"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("b", &["box"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("c", &["box", "box"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("d", &["other"], 1_000_000, 1000),
+        ];
+        let conflicts = conflicting_inputs(&transactions);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts["box"], ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn tracks_double_spends_across_snapshots() {
+        let mut tracked = vec![];
+        let first = vec![
+            UnconfirmedTxs::for_test("a", &["box"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("b", &["box"], 1_000_000, 1000),
+        ];
+        track_double_spends(&mut tracked, &first, 1);
+        assert_eq!(tracked.len(), 1);
+        assert_eq!(tracked[0].tx_ids, ["a", "b"]);
+        assert!(tracked[0].active);
+
+        /* A third spender joins, the conflict stays the same entry */
+        let second = vec![
+            UnconfirmedTxs::for_test("a", &["box"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("c", &["box"], 1_000_000, 1000),
+        ];
+        track_double_spends(&mut tracked, &second, 2);
+        assert_eq!(tracked.len(), 1);
+        assert_eq!(tracked[0].tx_ids, ["a", "b", "c"]);
+        assert_eq!((tracked[0].first_seen, tracked[0].last_seen), (1, 2));
+
+        /* Resolved once a single spender is left, new conflicts come first */
+        let third = vec![
+            UnconfirmedTxs::for_test("a", &["box"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("d", &["other"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("e", &["other"], 1_000_000, 1000),
+        ];
+        track_double_spends(&mut tracked, &third, 3);
+        assert_eq!(tracked.len(), 2);
+        assert_eq!(tracked[0].box_id, "other");
+        assert!(tracked[0].active);
+        assert_eq!(tracked[1].box_id, "box");
+        assert!(!tracked[1].active);
+        assert_eq!(tracked[1].last_seen, 2);
+    }
+
+    #[test]
+    fn keeps_a_bounded_number_of_resolved_double_spends() {
+        let mut tracked = vec![];
+        for time in 0..MAX_RESOLVED_DOUBLE_SPENDS as u64 + 10 {
+            let box_id = format!("box{}", time);
+            let transactions = vec![
+                UnconfirmedTxs::for_test("a", &[&box_id], 1_000_000, 1000),
+                UnconfirmedTxs::for_test("b", &[&box_id], 1_000_000, 1000),
+            ];
+            track_double_spends(&mut tracked, &transactions, time);
+        }
+        assert_eq!(tracked.len(), MAX_RESOLVED_DOUBLE_SPENDS + 1);
+        assert_eq!(
+            tracked
+                .iter()
+                .filter(|double_spend| double_spend.active)
+                .count(),
+            1
+        );
+    }
+}
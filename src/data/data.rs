@@ -1,174 +1,923 @@
+#[cfg(feature = "server")]
+use crate::{
+    data::{
+        config::node,
+        conflicts::double_spends_of,
+        lifecycle::api_fetch_lifecycle,
+        token::{annotate_assets, annotate_output_assets},
+    },
+    utils::register::{decode_constant, Constant},
+};
+use crate::{
+    data::{conflicts::DoubleSpend, lifecycle::TransactionLifecycle},
+    utils::format::format_amount,
+};
 use dioxus::prelude::*;
+#[cfg(feature = "server")]
 use dioxus_logger::tracing;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
+#[cfg(feature = "server")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "server")]
+use std::fmt;
 
-const NODE_UNCONFIRMED_TRANSACTIONS: &str =
-    "http://api.sigmamining.xyz/transactions/unconfirmed?limit=10&offset=0";
+/* Spent input boxes looked up in the indexer at once */
+#[cfg(feature = "server")]
+const SPENT_BOX_CONCURRENCY: usize = 8;
+
+#[cfg(feature = "server")]
+const NODE_UNCONFIRMED_TRANSACTIONS: &str = "/transactions/unconfirmed";
+#[cfg(feature = "server")]
+const NODE_INFO: &str = "/info";
+#[cfg(feature = "server")]
+const NODE_BLOCKS_AT_HEIGHT: &str = "/blocks/at/";
+#[cfg(feature = "server")]
+pub(crate) const NODE_BLOCKS: &str = "/blocks/";
+#[cfg(feature = "server")]
+const NODE_UTXO_WITH_POOL_BY_IDS: &str = "/utxo/withPool/byIds";
+#[cfg(feature = "server")]
+pub(crate) const NODE_BLOCKCHAIN_BOX_BY_ID: &str = "/blockchain/box/byId/";
+#[cfg(feature = "server")]
+pub(crate) const NODE_UNCONFIRMED_BY_TRANSACTION_ID: &str =
+    "/transactions/unconfirmed/byTransactionId/";
+#[cfg(feature = "server")]
+pub(crate) const NODE_BLOCKCHAIN_TRANSACTION_BY_ID: &str = "/blockchain/transaction/byId/";
+#[cfg(feature = "server")]
+pub(crate) const NODE_UTXO_WITH_POOL_BY_ID: &str = "/utxo/withPool/byId/";
+#[cfg(feature = "server")]
+pub(crate) const NODE_BLOCKCHAIN_TOKEN_BY_ID: &str = "/blockchain/token/byId/";
+
+/* ErgoTree of the standard miner fee contract, outputs guarded by it pay the transaction fee */
+pub const FEE_CONTRACT_ERGO_TREE: &str = "1005040004000e36100204a00b08cd0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798ea02d192a39a8cc7a701730073011001020402d19683030193a38cc7b2a57300000193c2b2a57301007473027303830108cdeeac93b1a57304";
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Asset {
     pub token_id: String,
-    pub amount: u32,
+    pub amount: u64,
+    /* EIP-4 metadata filled in by the server, not part of the node's box format */
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub decimals: u32,
+}
+
+impl Asset {
+    /* The token name, or a prefix of its id for tokens without metadata */
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("{}...", &self.token_id[..self.token_id.len().min(8)]),
+        }
+    }
+
+    pub fn display_amount(&self) -> String {
+        format_amount(self.amount, self.decimals)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingProof {
+    pub proof_bytes: String,
+    #[serde(default)]
+    pub extension: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Input {
     pub box_id: String,
+    #[serde(default)]
+    pub spending_proof: SpendingProof,
+    /* The node only returns box ids for inputs, None until resolved */
+    #[serde(default)]
+    pub value: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataInput {
+    pub box_id: String,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Output {
     pub box_id: String,
-    pub value: u32,
+    pub value: u64,
+    pub ergo_tree: String,
+    #[serde(default)]
     pub assets: Vec<Asset>,
     pub creation_height: u32,
+    #[serde(default, deserialize_with = "deserialize_registers")]
+    pub additional_registers: BTreeMap<String, String>,
+    #[serde(rename = "transactionId")]
     pub tx_id: String,
+    pub index: u32,
+}
+
+/* The indexer wraps each register as {serializedValue, sigmaType, renderedValue},
+ * the rest of the node API only returns the serialized hex */
+fn deserialize_registers<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Register {
+        Serialized(String),
+        #[serde(rename_all = "camelCase")]
+        Indexed {
+            serialized_value: String,
+        },
+    }
+
+    let registers = BTreeMap::<String, Register>::deserialize(deserializer)?;
+    Ok(registers
+        .into_iter()
+        .map(|(register, value)| match value {
+            Register::Serialized(serialized_value) | Register::Indexed { serialized_value } => {
+                (register, serialized_value)
+            }
+        })
+        .collect())
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct UnconfirmedTxs {
     pub id: String,
     pub inputs: Vec<Input>,
-    pub data_inputs: Vec<String>,
+    #[serde(default)]
+    pub data_inputs: Vec<DataInput>,
     pub outputs: Vec<Output>,
+    #[serde(default)]
     pub size: u32,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VectorUnconfirmedTxs {
-    node_array: Value,
+    #[serde(skip)]
+    node_array: Vec<Value>,
     pub unconfirmed_txs: Vec<UnconfirmedTxs>,
+    /* Number of transactions in the node mempool, can exceed unconfirmed_txs.len() when capped */
+    pub mempool_size: u32,
+    /* Sum of size in bytes and of output value in nanoERG over unconfirmed_txs */
+    pub total_size: u64,
+    pub total_value: u64,
+    /* Unix time in milliseconds at which the server poller took this snapshot */
+    pub snapshot_time: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub id: String,
+    pub parent_id: String,
+    pub version: u32,
+    pub height: u32,
+    pub timestamp: u64,
+    pub n_bits: u64,
+    pub difficulty: String,
+    pub votes: String,
+    pub state_root: String,
+    pub ad_proofs_root: String,
+    pub transactions_root: String,
+    pub extension_hash: String,
+    pub miner_pk: String,
+    pub size: u32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Extension {
+    pub digest: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/* A single transaction with its position in the chain, if any */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionDetails {
+    pub transaction: UnconfirmedTxs,
+    /* Inclusion fields are None while the transaction is still in the mempool */
+    pub inclusion_height: Option<u32>,
+    pub block_id: Option<String>,
+    pub timestamp: Option<u64>,
+    pub confirmations: u32,
+    /* Active conflicts over the transaction's inputs, only for mempool transactions */
+    #[serde(default)]
+    pub double_spends: Vec<DoubleSpend>,
+    /* When this server saw the transaction in the mempool, None if it never did */
+    #[serde(default)]
+    pub lifecycle: Option<TransactionLifecycle>,
+}
+
+/* A non-mandatory register (R4-R9) with its constant decoded when possible */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedRegister {
+    pub register: String,
+    pub serialized_value: String,
+    pub sigma_type: Option<String>,
+    pub value: Option<String>,
+    /* Coll[Byte] values that are readable UTF-8 text */
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpendingStatus {
+    #[default]
+    Unspent,
+    SpentInMempool {
+        tx_id: String,
+    },
+    SpentInBlock {
+        tx_id: String,
+    },
+}
+
+/* A single box with its registers decoded and its spending status */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoxDetails {
+    pub output: Output,
+    pub registers: Vec<DecodedRegister>,
+    pub status: SpendingStatus,
+    /* None while the creating transaction is still in the mempool */
+    pub inclusion_height: Option<u32>,
+    /* Only known when the node runs the extra indexer */
+    pub address: Option<String>,
+}
+
+/* A full block as returned by the node's /blocks/{id} endpoint.
+ * Confirmed transactions have the same shape as mempool ones, so they reuse UnconfirmedTxs. */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    pub header: BlockHeader,
+    pub transactions: Vec<UnconfirmedTxs>,
+    pub extension: Extension,
+    pub ad_proofs_size: u32,
+    pub size: u32,
+}
+
+impl UnconfirmedTxs {
+    /* Total nanoERG value of the transaction outputs, fee output included */
+    pub fn output_value(&self) -> u64 {
+        self.outputs.iter().map(|output| output.value).sum()
+    }
+
+    /* nanoERG paid to the miner fee contract */
+    pub fn fee(&self) -> u64 {
+        self.outputs
+            .iter()
+            .filter(|output| output.ergo_tree == FEE_CONTRACT_ERGO_TREE)
+            .map(|output| output.value)
+            .sum()
+    }
+
+    /* Miner fee in nanoERG per byte, 0 when the size is unknown */
+    pub fn fee_rate(&self) -> f64 {
+        match self.size {
+            0 => 0.0,
+            size => self.fee() as f64 / size as f64,
+        }
+    }
+
+    /* Tokens sent to the outputs, summed per token id in order of first appearance */
+    pub fn output_assets(&self) -> Vec<Asset> {
+        let mut assets: Vec<Asset> = vec![];
+        for asset in self.outputs.iter().flat_map(|output| output.assets.iter()) {
+            match assets
+                .iter_mut()
+                .find(|known| known.token_id == asset.token_id)
+            {
+                Some(known) => known.amount = known.amount.saturating_add(asset.amount),
+                None => assets.push(asset.clone()),
+            }
+        }
+        assets
+    }
+}
+
+/* Raised when a transaction returned by the node does not match the UnconfirmedTxs model */
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionParseError {
+    /* Position of the transaction in the node response */
+    pub index: usize,
+    pub tx_id: Option<String>,
+    pub message: String,
+}
+
+#[cfg(feature = "server")]
+impl fmt::Display for TransactionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse transaction #{} ({}): {}",
+            self.index,
+            self.tx_id.as_deref().unwrap_or("unknown id"),
+            self.message
+        )
+    }
 }
 
+#[cfg(feature = "server")]
+impl std::error::Error for TransactionParseError {}
+
+#[cfg(feature = "server")]
+impl UnconfirmedTxs {
+    pub fn from_node_values(
+        node_transactions: Vec<Value>,
+    ) -> Result<Vec<UnconfirmedTxs>, TransactionParseError> {
+        node_transactions
+            .into_iter()
+            .enumerate()
+            .map(|(index, transaction)| {
+                let tx_id = transaction["id"].as_str().map(str::to_string);
+                serde_json::from_value(transaction).map_err(|err| TransactionParseError {
+                    index,
+                    tx_id,
+                    message: err.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /* Fill in Input::value for every input of the given transactions.
+     * Boxes created inside the same set are resolved locally, the rest are looked up in the
+     * node's UTXO set (including the mempool) and finally in the indexer for already spent boxes.
+     * Lookups are best effort, unresolved inputs keep a value of None. */
+    pub async fn api_fetch_input_values(transactions: &mut [UnconfirmedTxs]) {
+        let mut box_values: HashMap<String, u64> = HashMap::new();
+        for transaction in transactions.iter() {
+            for output in transaction.outputs.iter() {
+                box_values.insert(output.box_id.clone(), output.value);
+            }
+        }
+
+        let unresolved = |box_values: &HashMap<String, u64>| -> Vec<String> {
+            let mut box_ids: Vec<String> = transactions
+                .iter()
+                .flat_map(|transaction| transaction.inputs.iter())
+                .filter(|input| !box_values.contains_key(&input.box_id))
+                .map(|input| input.box_id.clone())
+                .collect();
+            box_ids.sort();
+            box_ids.dedup();
+            box_ids
+        };
+
+        /* Unspent boxes, including outputs of other mempool transactions */
+        let box_ids = unresolved(&box_values);
+        if !box_ids.is_empty() {
+            match Self::api_fetch_boxes(&box_ids).await {
+                Ok(boxes) => {
+                    for node_box in boxes {
+                        box_values.insert(node_box.box_id, node_box.value);
+                    }
+                }
+                Err(err) => tracing::warn!("Failed to fetch input boxes: {}", err),
+            }
+        }
+
+        /* Spent boxes, only available when the node runs the extra indexer */
+        let spent_boxes: Vec<(String, Result<Option<Value>, ServerFnError>)> =
+            stream::iter(unresolved(&box_values))
+                .map(|box_id| async move {
+                    let node_box =
+                        api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_BOX_BY_ID, box_id))
+                            .await;
+                    (box_id, node_box)
+                })
+                .buffer_unordered(SPENT_BOX_CONCURRENCY)
+                .collect()
+                .await;
+        let mut missing = 0;
+        for (box_id, node_box) in spent_boxes {
+            match node_box {
+                Ok(Some(node_box)) => match node_box["value"].as_u64() {
+                    Some(value) => {
+                        box_values.insert(box_id, value);
+                    }
+                    None => missing += 1,
+                },
+                Ok(None) => missing += 1,
+                Err(err) => {
+                    missing += 1;
+                    tracing::warn!("Failed to fetch spent box {}: {}", box_id, err);
+                }
+            }
+        }
+        if missing > 0 {
+            tracing::warn!("{} input values left unresolved", missing);
+        }
+
+        for transaction in transactions.iter_mut() {
+            for input in transaction.inputs.iter_mut() {
+                if let Some(value) = box_values.get(&input.box_id) {
+                    input.value = Some(*value);
+                }
+            }
+        }
+    }
+
+    async fn api_fetch_boxes(box_ids: &[String]) -> Result<Vec<Output>, ServerFnError> {
+        let boxes = node()
+            .post(NODE_UTXO_WITH_POOL_BY_IDS)
+            .json(box_ids)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(boxes)
+    }
+}
+
+#[cfg(feature = "server")]
 impl VectorUnconfirmedTxs {
     pub async fn default() -> VectorUnconfirmedTxs {
         VectorUnconfirmedTxs {
-            node_array: Value::default(),
+            node_array: Vec::default(),
             unconfirmed_txs: Vec::default(),
+            mempool_size: 0,
+            total_size: 0,
+            total_value: 0,
+            snapshot_time: 0,
         }
     }
 
     pub async fn get_data(&mut self) -> Result<(), ServerFnError> {
         self.api_fetch_unconfirmed_transactions().await?;
         self.process_api_data().await?;
+        UnconfirmedTxs::api_fetch_input_values(&mut self.unconfirmed_txs).await;
+        annotate_assets(&mut self.unconfirmed_txs).await;
         Ok(())
     }
 
+    /* Page through the mempool until the node runs out of transactions or the cap is hit */
     async fn api_fetch_unconfirmed_transactions(&mut self) -> Result<(), ServerFnError> {
-        self.node_array = Client::new()
-            .get(format!("{}", NODE_UNCONFIRMED_TRANSACTIONS))
+        let page_size = node().config.page_size as usize;
+        let max_transactions = node().config.max_unconfirmed_transactions as usize;
+
+        self.node_array.clear();
+        loop {
+            let page: Vec<Value> = node()
+                .get(&format!(
+                    "{}?limit={}&offset={}",
+                    NODE_UNCONFIRMED_TRANSACTIONS,
+                    page_size,
+                    self.node_array.len()
+                ))
+                .send()
+                .await?
+                .json()
+                .await?;
+            let exhausted = page.len() < page_size;
+            self.node_array.extend(page);
+
+            if exhausted {
+                self.mempool_size = self.node_array.len() as u32;
+                break;
+            }
+            if self.node_array.len() >= max_transactions {
+                self.node_array.truncate(max_transactions);
+                self.mempool_size = self.api_fetch_mempool_size().await?;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    async fn api_fetch_mempool_size(&self) -> Result<u32, ServerFnError> {
+        let info = api_fetch_info().await?;
+        Ok(info["unconfirmedCount"]
+            .as_u64()
+            .map(|count| count as u32)
+            .unwrap_or(self.node_array.len() as u32))
+    }
+
+    async fn process_api_data(&mut self) -> Result<(), ServerFnError> {
+        let node_array = std::mem::take(&mut self.node_array);
+        self.unconfirmed_txs = UnconfirmedTxs::from_node_values(node_array)?;
+
+        /* The mempool can shift between pages, drop transactions that were returned twice */
+        let mut seen = HashSet::new();
+        self.unconfirmed_txs
+            .retain(|transaction| seen.insert(transaction.id.clone()));
+
+        self.total_size = self
+            .unconfirmed_txs
+            .iter()
+            .map(|transaction| transaction.size as u64)
+            .sum();
+        self.total_value = self
+            .unconfirmed_txs
+            .iter()
+            .map(|transaction| transaction.output_value())
+            .sum();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+impl Block {
+    /* Resolve a height to the id(s) of the block(s) the node knows at that height.
+     * More than one id is returned while competing forks have not been resolved yet. */
+    pub async fn api_fetch_block_ids(block_height: u32) -> Result<Vec<String>, ServerFnError> {
+        let block_ids: Vec<String> = node()
+            .get(&format!("{}{}", NODE_BLOCKS_AT_HEIGHT, block_height))
             .send()
             .await?
             .json()
             .await?;
-        Ok(())
+        Ok(block_ids)
     }
-    async fn process_api_data(&mut self) -> Result<(), ServerFnError> {
-        if let serde_json::Value::Array(unconfirmed_array) = self.node_array.clone() {
-            for transaction in unconfirmed_array {
-                /* Store Transaction ID in self.id */
-                let id = transaction["id"].as_str().unwrap().to_string();
-
-                /* Store transaction inputs in self.inputs */
-                let mut inputs_vec = vec![];
-
-                match transaction["inputs"].as_array() {
-                    Some(inputs) => {
-                        for input in inputs {
-                            inputs_vec.push(Input {
-                                box_id: input["boxId"].as_str().unwrap().to_string(),
-                            })
-                        }
-                    }
-                    None => (),
-                }
 
-                /* Store dataInputs in self.data_inputs */
-                let mut data_input_vec = vec![];
-                match transaction["dataInputs"].as_array() {
-                    Some(data_inputs) => {
-                        for data_input in data_inputs {
-                            data_input_vec.push(data_input["boxId"].as_str().unwrap().to_string());
-                        }
-                    }
-                    None => (),
-                }
+    pub async fn api_fetch_block(block_id: &str) -> Result<Block, ServerFnError> {
+        let node_block: Value = node()
+            .get(&format!("{}{}", NODE_BLOCKS, block_id))
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(Block::from_node_value(node_block)?)
+    }
 
-                /* Store transaction outputs in self.outputs */
-                let mut outputs_vec = vec![];
-                for outputs in transaction["outputs"].as_array().unwrap() {
-                    outputs_vec.push(Output {
-                        box_id: {
-                            match outputs["boxId"].as_str() {
-                                Some(box_id) => box_id.to_string(),
-                                None => String::new(),
-                            }
-                        },
-                        value: outputs["value"].as_u64().unwrap() as u32,
-
-                        assets: {
-                            let mut assets_vec = Vec::new();
-                            for assets in outputs["assets"].as_array().unwrap() {
-                                assets_vec.push(Asset {
-                                    token_id: {
-                                        match assets["tokenId"].as_str() {
-                                            Some(token_id) => token_id.to_string(),
-                                            None => String::new(),
-                                        }
-                                    },
-                                    amount: {
-                                        match assets["amount"].as_u64() {
-                                            Some(amount) => amount as u32,
-                                            None => 0,
-                                        }
-                                    },
-                                });
-                            }
-                            /* Return Vec<Assets> */
-                            assets_vec
-                        },
-
-                        creation_height: {
-                            match outputs["creationHeight"].as_u64() {
-                                Some(creation_height) => creation_height as u32,
-                                None => 0,
-                            }
-                        },
-                        tx_id: {
-                            match outputs["transactionId"].as_str() {
-                                Some(tx_id) => tx_id.to_string(),
-                                None => String::new(),
-                            }
-                        },
-                    })
-                }
+    fn from_node_value(mut node_block: Value) -> Result<Block, TransactionParseError> {
+        let header = &node_block["header"];
 
-                /* Store transaction size in self.size */
-                let size = match transaction["size"].as_u64() {
-                    Some(size) => size as u32,
-                    None => 0,
-                };
-
-                self.unconfirmed_txs.push(UnconfirmedTxs {
-                    id: id,
-                    inputs: inputs_vec,
-                    data_inputs: data_input_vec,
-                    outputs: outputs_vec,
-                    size: size,
-                })
+        /* Store header fields in self.header */
+        let header = BlockHeader {
+            id: header["id"].as_str().unwrap_or_default().to_string(),
+            parent_id: header["parentId"].as_str().unwrap_or_default().to_string(),
+            version: header["version"].as_u64().unwrap_or_default() as u32,
+            height: header["height"].as_u64().unwrap_or_default() as u32,
+            timestamp: header["timestamp"].as_u64().unwrap_or_default(),
+            n_bits: header["nBits"].as_u64().unwrap_or_default(),
+            difficulty: match &header["difficulty"] {
+                Value::String(difficulty) => difficulty.clone(),
+                Value::Number(difficulty) => difficulty.to_string(),
+                _ => String::new(),
+            },
+            votes: header["votes"].as_str().unwrap_or_default().to_string(),
+            state_root: header["stateRoot"].as_str().unwrap_or_default().to_string(),
+            ad_proofs_root: header["adProofsRoot"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            transactions_root: header["transactionsRoot"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            extension_hash: header["extensionHash"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            miner_pk: header["powSolutions"]["pk"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            size: header["size"].as_u64().unwrap_or_default() as u32,
+        };
+
+        /* Store block transactions in self.transactions */
+        let transactions = match node_block["blockTransactions"]["transactions"].take() {
+            Value::Array(node_transactions) => UnconfirmedTxs::from_node_values(node_transactions)?,
+            _ => vec![],
+        };
+
+        /* Store extension key/value fields in self.extension */
+        let mut fields = vec![];
+        if let Some(node_fields) = node_block["extension"]["fields"].as_array() {
+            for field in node_fields {
+                fields.push((
+                    field[0].as_str().unwrap_or_default().to_string(),
+                    field[1].as_str().unwrap_or_default().to_string(),
+                ));
             }
         }
-        Ok(())
+        let extension = Extension {
+            digest: node_block["extension"]["digest"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            fields,
+        };
+
+        /* AD proofs are pruned by some nodes, in which case "adProofs" is null */
+        let ad_proofs_size = node_block["adProofs"]["size"].as_u64().unwrap_or_default() as u32;
+
+        Ok(Block {
+            header,
+            transactions,
+            extension,
+            ad_proofs_size,
+            size: node_block["size"].as_u64().unwrap_or_default() as u32,
+        })
     }
 }
 
+#[cfg(feature = "server")]
+impl TransactionDetails {
+    pub async fn api_fetch(tx_id: &str) -> Result<Option<TransactionDetails>, ServerFnError> {
+        if !is_node_id(tx_id) {
+            return Ok(None);
+        }
+        let Some(mut details) = Self::api_fetch_transaction(tx_id).await? else {
+            return Ok(None);
+        };
+        /* The lifecycle is an extra, the transaction is still shown without it */
+        match api_fetch_lifecycle(tx_id).await {
+            Ok(lifecycle) => details.lifecycle = lifecycle,
+            Err(err) => tracing::warn!("Failed to read lifecycle of {}: {}", tx_id, err),
+        }
+        Ok(Some(details))
+    }
+
+    /* Look the transaction up in the mempool first, then in the chain */
+    async fn api_fetch_transaction(
+        tx_id: &str,
+    ) -> Result<Option<TransactionDetails>, ServerFnError> {
+        let snapshot = crate::data::poller::latest_snapshot().await?;
+        if let Some(transaction) = snapshot
+            .unconfirmed_txs
+            .iter()
+            .find(|transaction| transaction.id == tx_id)
+        {
+            return Ok(Some(TransactionDetails {
+                transaction: transaction.clone(),
+                double_spends: double_spends_of(tx_id),
+                ..TransactionDetails::default()
+            }));
+        }
+
+        if let Some(mut transaction) = Self::api_fetch_unconfirmed(tx_id).await? {
+            UnconfirmedTxs::api_fetch_input_values(std::slice::from_mut(&mut transaction)).await;
+            annotate_assets(std::slice::from_mut(&mut transaction)).await;
+            return Ok(Some(TransactionDetails {
+                transaction,
+                double_spends: double_spends_of(tx_id),
+                ..TransactionDetails::default()
+            }));
+        }
+
+        Self::api_fetch_confirmed(tx_id).await
+    }
+
+    async fn api_fetch_unconfirmed(tx_id: &str) -> Result<Option<UnconfirmedTxs>, ServerFnError> {
+        let Some(node_transaction) =
+            api_fetch_optional(format!("{}{}", NODE_UNCONFIRMED_BY_TRANSACTION_ID, tx_id)).await?
+        else {
+            return Ok(None);
+        };
+        Ok(UnconfirmedTxs::from_node_values(vec![node_transaction])?.pop())
+    }
+
+    /* Confirmed transactions are only available when the node runs the extra indexer */
+    async fn api_fetch_confirmed(tx_id: &str) -> Result<Option<TransactionDetails>, ServerFnError> {
+        let Some(node_transaction) =
+            api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_TRANSACTION_BY_ID, tx_id)).await?
+        else {
+            return Ok(None);
+        };
+
+        let inclusion_height = node_transaction["inclusionHeight"]
+            .as_u64()
+            .map(|height| height as u32);
+        let block_id = node_transaction["blockId"].as_str().map(str::to_string);
+        let timestamp = node_transaction["timestamp"].as_u64();
+        let confirmations = node_transaction["numConfirmations"]
+            .as_u64()
+            .unwrap_or_default() as u32;
+
+        /* Indexed inputs are full boxes, so their values come with the transaction */
+        let Some(mut transaction) = UnconfirmedTxs::from_node_values(vec![node_transaction])?.pop()
+        else {
+            return Ok(None);
+        };
+        annotate_assets(std::slice::from_mut(&mut transaction)).await;
+        Ok(Some(TransactionDetails {
+            transaction,
+            inclusion_height,
+            block_id,
+            timestamp,
+            confirmations,
+            double_spends: vec![],
+            lifecycle: None,
+        }))
+    }
+}
+
+#[cfg(feature = "server")]
+impl DecodedRegister {
+    pub fn decode(register: &str, serialized_value: &str) -> DecodedRegister {
+        let constant = decode_constant(serialized_value)
+            .inspect_err(|err| tracing::debug!("Failed to decode {}: {}", register, err))
+            .ok();
+        DecodedRegister {
+            register: register.to_string(),
+            serialized_value: serialized_value.to_string(),
+            sigma_type: constant.as_ref().map(|constant| constant.tpe.to_string()),
+            value: constant.as_ref().map(|constant| constant.value.to_string()),
+            text: constant.as_ref().and_then(Constant::as_text),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl BoxDetails {
+    /* Boxes created in the mempool come from the poller snapshot, the rest from the node */
+    pub async fn api_fetch(box_id: &str) -> Result<Option<BoxDetails>, ServerFnError> {
+        if !is_node_id(box_id) {
+            return Ok(None);
+        }
+        let snapshot = crate::data::poller::latest_snapshot().await?;
+        let spending_tx = snapshot.unconfirmed_txs.iter().find(|transaction| {
+            transaction
+                .inputs
+                .iter()
+                .any(|input| input.box_id == box_id)
+        });
+        let mempool_output = snapshot
+            .unconfirmed_txs
+            .iter()
+            .flat_map(|transaction| transaction.outputs.iter())
+            .find(|output| output.box_id == box_id);
+
+        let mut details = match mempool_output {
+            Some(output) => BoxDetails {
+                output: output.clone(),
+                ..BoxDetails::default()
+            },
+            None => match Self::api_fetch_indexed(box_id).await? {
+                Some(details) => details,
+                None => match Self::api_fetch_unspent(box_id).await? {
+                    Some(details) => details,
+                    None => return Ok(None),
+                },
+            },
+        };
+
+        if let Some(transaction) = spending_tx {
+            details.status = SpendingStatus::SpentInMempool {
+                tx_id: transaction.id.clone(),
+            };
+        }
+        details.registers = details
+            .output
+            .additional_registers
+            .iter()
+            .map(|(register, serialized_value)| DecodedRegister::decode(register, serialized_value))
+            .collect();
+        annotate_output_assets(std::slice::from_mut(&mut details.output)).await;
+        Ok(Some(details))
+    }
+
+    /* Spent and unspent boxes, only available when the node runs the extra indexer */
+    async fn api_fetch_indexed(box_id: &str) -> Result<Option<BoxDetails>, ServerFnError> {
+        let Some(node_box) =
+            api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_BOX_BY_ID, box_id)).await?
+        else {
+            return Ok(None);
+        };
+
+        let status = match node_box["spentTransactionId"].as_str() {
+            Some(tx_id) => SpendingStatus::SpentInBlock {
+                tx_id: tx_id.to_string(),
+            },
+            None => SpendingStatus::Unspent,
+        };
+        let inclusion_height = node_box["inclusionHeight"]
+            .as_u64()
+            .map(|height| height as u32);
+        let address = node_box["address"].as_str().map(str::to_string);
+
+        Ok(Some(BoxDetails {
+            output: serde_json::from_value(node_box)?,
+            status,
+            inclusion_height,
+            address,
+            ..BoxDetails::default()
+        }))
+    }
+
+    /* Without the indexer only unspent boxes can be looked up */
+    async fn api_fetch_unspent(box_id: &str) -> Result<Option<BoxDetails>, ServerFnError> {
+        let Some(node_box) =
+            api_fetch_optional(format!("{}{}", NODE_UTXO_WITH_POOL_BY_ID, box_id)).await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(BoxDetails {
+            output: serde_json::from_value(node_box)?,
+            ..BoxDetails::default()
+        }))
+    }
+}
+
+/* Transaction, box, block and token ids are 32 bytes in hex */
+#[cfg(feature = "server")]
+pub fn is_node_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/* GET a node endpoint, None when the node answers 404: it does not know the id, or lacks the
+ * indexer for /blockchain endpoints. Any other failure is an error, not a missing id. */
+#[cfg(feature = "server")]
+pub async fn api_fetch_optional(path: String) -> Result<Option<Value>, ServerFnError> {
+    let response = node().get(&path).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    Ok(Some(response.error_for_status()?.json().await?))
+}
+
+/* The node's /info status: heights, best block ids, mempool count and chain parameters */
+#[cfg(feature = "server")]
+pub async fn api_fetch_info() -> Result<Value, ServerFnError> {
+    let info = node().get(NODE_INFO).send().await?.json().await?;
+    Ok(info)
+}
+
+/* Served from the snapshot cached by the background poller, not from the node */
 #[server(GetServerData)]
 pub async fn get_server_data() -> Result<VectorUnconfirmedTxs, ServerFnError> {
-    let mut data = VectorUnconfirmedTxs::default().await;
-    data.get_data().await?;
+    let data = crate::data::poller::latest_snapshot().await?;
+
+    // tracing::info!("Data processed: {:?}", data.unconfirmed_txs);
+    Ok(data.as_ref().clone())
+}
+
+#[server(GetBlockData)]
+pub async fn get_block_data(block_height: u32) -> Result<Vec<Block>, ServerFnError> {
+    let mut blocks = vec![];
+    for block_id in Block::api_fetch_block_ids(block_height).await? {
+        let mut block = Block::api_fetch_block(&block_id).await?;
+        UnconfirmedTxs::api_fetch_input_values(&mut block.transactions).await;
+        annotate_assets(&mut block.transactions).await;
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+#[server(GetTransactionData)]
+pub async fn get_transaction_data(
+    tx_id: String,
+) -> Result<Option<TransactionDetails>, ServerFnError> {
+    TransactionDetails::api_fetch(&tx_id).await
+}
 
-    // tracing::info!("Data processed: {:?}", data.node_array);
-    Ok(data)
+#[server(GetBoxData)]
+pub async fn get_box_data(box_id: String) -> Result<Option<BoxDetails>, ServerFnError> {
+    BoxDetails::api_fetch(&box_id).await
+}
+
+/* A mempool transaction spending the given boxes, paying fee and creating the box "{id}:0" */
+#[cfg(all(test, feature = "server"))]
+impl UnconfirmedTxs {
+    pub fn for_test(id: &str, inputs: &[&str], fee: u64, size: u32) -> UnconfirmedTxs {
+        UnconfirmedTxs {
+            id: id.to_string(),
+            inputs: inputs
+                .iter()
+                .map(|box_id| Input {
+                    box_id: box_id.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            outputs: vec![
+                Output {
+                    box_id: format!("{}:0", id),
+                    value: 1_000_000_000,
+                    tx_id: id.to_string(),
+                    ..Default::default()
+                },
+                Output {
+                    box_id: format!("{}:1", id),
+                    value: fee,
+                    ergo_tree: FEE_CONTRACT_ERGO_TREE.to_string(),
+                    tx_id: id.to_string(),
+                    index: 1,
+                    ..Default::default()
+                },
+            ],
+            size,
+            ..Default::default()
+        }
+    }
+}
+
+/* A complete (uncapped) snapshot of the given transactions */
+#[cfg(all(test, feature = "server"))]
+impl VectorUnconfirmedTxs {
+    pub fn for_test(transactions: Vec<UnconfirmedTxs>, snapshot_time: u64) -> VectorUnconfirmedTxs {
+        VectorUnconfirmedTxs {
+            mempool_size: transactions.len() as u32,
+            unconfirmed_txs: transactions,
+            snapshot_time,
+            ..Default::default()
+        }
+    }
 }
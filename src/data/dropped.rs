@@ -0,0 +1,287 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::lifecycle::TransactionOutcome;
+
+#[cfg(feature = "server")]
+use crate::data::{
+    conflicts::DoubleSpend,
+    data::{UnconfirmedTxs, VectorUnconfirmedTxs},
+    poller::poller,
+    stream::ConfirmedBlock,
+};
+#[cfg(feature = "server")]
+use std::collections::HashSet;
+
+/* Dropped transactions kept by the poller */
+#[cfg(feature = "server")]
+const MAX_DROPPED_TRANSACTIONS: usize = 100;
+
+/* A transaction that left the mempool without being included in a block,
+ * times in unix milliseconds */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DroppedTransaction {
+    pub tx_id: String,
+    pub size: u32,
+    pub fee: u64,
+    pub fee_rate: f64,
+    /* Time of the last snapshot the transaction was in */
+    pub last_seen: u64,
+    /* Time of the first snapshot without it */
+    pub left_at: u64,
+    /* DoubleSpent when another transaction spent one of its inputs, Dropped otherwise */
+    pub outcome: TransactionOutcome,
+}
+
+/* Transactions of the previous snapshot missing from the current one */
+#[cfg(feature = "server")]
+#[derive(Debug, Default, Clone)]
+pub struct Departures {
+    /* Included in one of the blocks found since the previous snapshot */
+    pub confirmed: Vec<String>,
+    pub dropped: Vec<DroppedTransaction>,
+}
+
+/* Split the transactions that left the mempool between the two snapshots. blocks must be every
+ * block found since the previous snapshot, and the current snapshot must hold the whole mempool,
+ * or transactions confirmed in a missing block or past the fetch cap would look dropped. */
+#[cfg(feature = "server")]
+pub fn classify_departures(
+    previous: &VectorUnconfirmedTxs,
+    current: &VectorUnconfirmedTxs,
+    blocks: &[&ConfirmedBlock],
+    double_spends: &[DoubleSpend],
+) -> Departures {
+    let current_ids: HashSet<&str> = current
+        .unconfirmed_txs
+        .iter()
+        .map(|transaction| transaction.id.as_str())
+        .collect();
+    let included_ids: HashSet<&str> = blocks
+        .iter()
+        .flat_map(|block| block.transaction_ids.iter())
+        .map(|tx_id| tx_id.as_str())
+        .collect();
+    /* Boxes spent by the transactions still in the mempool or included in the new blocks */
+    let spent_box_ids: HashSet<&str> = current
+        .unconfirmed_txs
+        .iter()
+        .flat_map(|transaction| transaction.inputs.iter())
+        .map(|input| input.box_id.as_str())
+        .chain(
+            blocks
+                .iter()
+                .flat_map(|block| block.spent_box_ids.iter())
+                .map(|box_id| box_id.as_str()),
+        )
+        .collect();
+
+    let (confirmed, dropped): (Vec<&UnconfirmedTxs>, Vec<&UnconfirmedTxs>) = previous
+        .unconfirmed_txs
+        .iter()
+        .filter(|transaction| !current_ids.contains(transaction.id.as_str()))
+        .partition(|transaction| included_ids.contains(transaction.id.as_str()));
+
+    let dropped = dropped
+        .into_iter()
+        .map(|transaction| {
+            let double_spent = transaction
+                .inputs
+                .iter()
+                .any(|input| spent_box_ids.contains(input.box_id.as_str()))
+                || double_spends
+                    .iter()
+                    .any(|double_spend| double_spend.tx_ids.contains(&transaction.id));
+            DroppedTransaction {
+                tx_id: transaction.id.clone(),
+                size: transaction.size,
+                fee: transaction.fee(),
+                fee_rate: transaction.fee_rate(),
+                last_seen: previous.snapshot_time,
+                left_at: current.snapshot_time,
+                outcome: match double_spent {
+                    true => TransactionOutcome::DoubleSpent,
+                    false => TransactionOutcome::Dropped,
+                },
+            }
+        })
+        .collect();
+
+    Departures {
+        confirmed: confirmed
+            .into_iter()
+            .map(|transaction| transaction.id.clone())
+            .collect(),
+        dropped,
+    }
+}
+
+/* Add the newly dropped transactions, newest first, and forget those confirmed since */
+#[cfg(feature = "server")]
+pub fn track_dropped(
+    tracked: &mut Vec<DroppedTransaction>,
+    dropped: Vec<DroppedTransaction>,
+    new_blocks: &[ConfirmedBlock],
+) {
+    tracked.retain(|transaction| {
+        !new_blocks
+            .iter()
+            .any(|block| block.transaction_ids.contains(&transaction.tx_id))
+    });
+    tracked.splice(0..0, dropped);
+    tracked.truncate(MAX_DROPPED_TRANSACTIONS);
+}
+
+#[server(GetDroppedTransactions)]
+pub async fn get_dropped_transactions() -> Result<Vec<DroppedTransaction>, ServerFnError> {
+    Ok(poller().borrow().dropped.clone())
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    fn block(height: u32, transaction_ids: &[&str], spent_box_ids: &[&str]) -> ConfirmedBlock {
+        ConfirmedBlock {
+            height,
+            transaction_ids: transaction_ids.iter().map(|id| id.to_string()).collect(),
+            spent_box_ids: spent_box_ids.iter().map(|id| id.to_string()).collect(),
+            ..ConfirmedBlock::default()
+        }
+    }
+
+    fn outcomes(departures: &Departures) -> Vec<(&str, TransactionOutcome)> {
+        departures
+            .dropped
+            .iter()
+            .map(|transaction| (transaction.tx_id.as_str(), transaction.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn confirms_departures_found_in_any_intervening_block() {
+        let previous = VectorUnconfirmedTxs::for_test(
+            vec![
+                UnconfirmedTxs::for_test("a", &["box_a"], 1_000_000, 200),
+                UnconfirmedTxs::for_test("b", &["box_b"], 1_000_000, 200),
+                UnconfirmedTxs::for_test("c", &["box_c"], 1_000_000, 200),
+            ],
+            1_000,
+        );
+        let current = VectorUnconfirmedTxs::for_test(
+            vec![UnconfirmedTxs::for_test("c", &["box_c"], 1_000_000, 200)],
+            2_000,
+        );
+        let first = block(10, &["a"], &["box_a"]);
+        let second = block(11, &["b"], &["box_b"]);
+
+        let departures = classify_departures(&previous, &current, &[&first, &second], &[]);
+        assert_eq!(departures.confirmed, vec!["a", "b"]);
+        assert!(departures.dropped.is_empty());
+    }
+
+    #[test]
+    fn marks_replaced_departures_as_double_spent() {
+        let previous = VectorUnconfirmedTxs::for_test(
+            vec![
+                UnconfirmedTxs::for_test("replaced", &["box_a"], 1_000_000, 200),
+                UnconfirmedTxs::for_test("mined_over", &["box_b"], 1_000_000, 200),
+            ],
+            1_000,
+        );
+        /* The replacement is still in the mempool, the other conflict was mined */
+        let current = VectorUnconfirmedTxs::for_test(
+            vec![UnconfirmedTxs::for_test(
+                "replacement",
+                &["box_a"],
+                2_000_000,
+                200,
+            )],
+            2_000,
+        );
+        let mined = block(10, &["winner"], &["box_b"]);
+
+        let departures = classify_departures(&previous, &current, &[&mined], &[]);
+        assert!(departures.confirmed.is_empty());
+        assert_eq!(
+            outcomes(&departures),
+            vec![
+                ("replaced", TransactionOutcome::DoubleSpent),
+                ("mined_over", TransactionOutcome::DoubleSpent),
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_departures_tracked_as_double_spends() {
+        let previous = VectorUnconfirmedTxs::for_test(
+            vec![UnconfirmedTxs::for_test("a", &["box_a"], 1_000_000, 200)],
+            1_000,
+        );
+        let current = VectorUnconfirmedTxs::for_test(vec![], 2_000);
+        let double_spend = DoubleSpend {
+            box_id: "box_a".to_string(),
+            tx_ids: vec!["a".to_string(), "b".to_string()],
+            ..DoubleSpend::default()
+        };
+
+        let departures = classify_departures(&previous, &current, &[], &[double_spend]);
+        assert_eq!(
+            outcomes(&departures),
+            vec![("a", TransactionOutcome::DoubleSpent)]
+        );
+    }
+
+    #[test]
+    fn drops_plain_departures() {
+        let previous = VectorUnconfirmedTxs::for_test(
+            vec![UnconfirmedTxs::for_test("a", &["box_a"], 1_000_000, 200)],
+            1_000,
+        );
+        let current = VectorUnconfirmedTxs::for_test(vec![], 2_000);
+        let unrelated = block(10, &["x"], &["box_x"]);
+
+        let departures = classify_departures(&previous, &current, &[&unrelated], &[]);
+        assert!(departures.confirmed.is_empty());
+        assert_eq!(
+            departures.dropped,
+            vec![DroppedTransaction {
+                tx_id: "a".to_string(),
+                size: 200,
+                fee: 1_000_000,
+                fee_rate: 5_000.0,
+                last_seen: 1_000,
+                left_at: 2_000,
+                outcome: TransactionOutcome::Dropped,
+            }]
+        );
+    }
+
+    #[test]
+    fn forgets_dropped_transactions_confirmed_later() {
+        let dropped = |tx_id: &str| DroppedTransaction {
+            tx_id: tx_id.to_string(),
+            size: 200,
+            fee: 1_000_000,
+            fee_rate: 5_000.0,
+            last_seen: 1_000,
+            left_at: 2_000,
+            outcome: TransactionOutcome::Dropped,
+        };
+        let mut tracked = vec![dropped("a"), dropped("b")];
+
+        track_dropped(&mut tracked, vec![dropped("c")], &[block(10, &["a"], &[])]);
+        let tx_ids: Vec<&str> = tracked
+            .iter()
+            .map(|transaction| transaction.tx_id.as_str())
+            .collect();
+        assert_eq!(tx_ids, vec!["c", "b"]);
+
+        let many = (0..MAX_DROPPED_TRANSACTIONS + 5)
+            .map(|index| dropped(&index.to_string()))
+            .collect();
+        track_dropped(&mut tracked, many, &[]);
+        assert_eq!(tracked.len(), MAX_DROPPED_TRANSACTIONS);
+        assert_eq!(tracked[0].tx_id, "0");
+    }
+}
@@ -0,0 +1,361 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::data::UnconfirmedTxs;
+
+#[cfg(feature = "server")]
+use crate::data::data::api_fetch_info;
+
+/* Percentiles reported besides the minimum, median and maximum */
+#[cfg(feature = "server")]
+const FEE_RATE_PERCENTILES: [u32; 4] = [10, 25, 75, 90];
+
+/* Fee rate bands in nanoERG per byte: lower bound and chart color, the last band is open ended.
+ * Shared by the histogram buckets and every chart colored by fee rate. */
+pub const FEE_RATE_BANDS: [(f64, &str); 7] = [
+    (0.0, "#60a5fa"),
+    (1000.0, "#22d3ee"),
+    (2000.0, "#34d399"),
+    (5000.0, "#facc15"),
+    (10000.0, "#fb923c"),
+    (20000.0, "#f87171"),
+    (50000.0, "#e879f9"),
+];
+
+/* Smallest fee accepted under the node's default mempool policy, 0.001 ERG */
+#[cfg(feature = "server")]
+const MIN_FEE: u64 = 1_000_000;
+
+/* Used when the node does not report its chain parameters */
+#[cfg(feature = "server")]
+const DEFAULT_MAX_BLOCK_SIZE: u64 = 1_271_009;
+
+/* A one input, two outputs payment plus the fee output */
+#[cfg(feature = "server")]
+pub const TYPICAL_TRANSACTION_SIZE: u32 = 250;
+
+/* Number of blocks within which each recommendation should confirm */
+#[cfg(feature = "server")]
+const FAST_TARGET_BLOCKS: u32 = 1;
+#[cfg(feature = "server")]
+const NORMAL_TARGET_BLOCKS: u32 = 3;
+#[cfg(feature = "server")]
+const SLOW_TARGET_BLOCKS: u32 = 6;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeePercentile {
+    pub percentile: u32,
+    pub fee_rate: f64,
+}
+
+/* Fees paid over a mempool snapshot, rates in nanoERG per byte */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeStats {
+    pub transaction_count: u32,
+    /* nanoERG paid to the miner fee contract by all transactions */
+    pub total_fees: u64,
+    pub min_fee_rate: f64,
+    pub median_fee_rate: f64,
+    pub max_fee_rate: f64,
+    pub percentiles: Vec<FeePercentile>,
+    pub snapshot_time: u64,
+}
+
+/* Mempool transactions whose fee rate falls in [lower_bound, upper_bound) */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeRateBucket {
+    pub lower_bound: f64,
+    pub upper_bound: Option<f64>,
+    pub transaction_count: u32,
+    pub total_size: u64,
+}
+
+impl FeeRateBucket {
+    pub fn label(&self) -> String {
+        match self.upper_bound {
+            Some(upper_bound) => format!("{:.0} - {:.0}", self.lower_bound, upper_bound),
+            None => format!(">= {:.0}", self.lower_bound),
+        }
+    }
+}
+
+/* Empty buckets covering every fee rate, one per band, lowest first */
+pub fn fee_rate_buckets() -> Vec<FeeRateBucket> {
+    FEE_RATE_BANDS
+        .iter()
+        .enumerate()
+        .map(|(index, &(lower_bound, _))| FeeRateBucket {
+            lower_bound,
+            upper_bound: FEE_RATE_BANDS
+                .get(index + 1)
+                .map(|&(upper_bound, _)| upper_bound),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/* Index into FEE_RATE_BANDS and fee_rate_buckets() of the band the fee rate falls in */
+pub fn fee_rate_bucket(fee_rate: f64) -> usize {
+    FEE_RATE_BANDS
+        .iter()
+        .rposition(|&(lower_bound, _)| fee_rate >= lower_bound)
+        .unwrap_or_default()
+}
+
+pub fn fee_rate_histogram(transactions: &[UnconfirmedTxs]) -> Vec<FeeRateBucket> {
+    let mut buckets = fee_rate_buckets();
+    for transaction in transactions {
+        let bucket = &mut buckets[fee_rate_bucket(transaction.fee_rate())];
+        bucket.transaction_count += 1;
+        bucket.total_size += transaction.size as u64;
+    }
+    buckets
+}
+
+/* Fee to pay for a transaction to confirm within target_blocks */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeRecommendation {
+    pub target_blocks: u32,
+    pub fee_rate: f64,
+    /* nanoERG, never below the minimum fee */
+    pub fee: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    /* Size in bytes of the transaction the fees are computed for */
+    pub transaction_size: u32,
+    pub max_block_size: u64,
+    pub fast: FeeRecommendation,
+    pub normal: FeeRecommendation,
+    pub slow: FeeRecommendation,
+    pub snapshot_time: u64,
+}
+
+/* Nearest-rank percentile over rates sorted in ascending order */
+#[cfg(feature = "server")]
+fn percentile(sorted_fee_rates: &[f64], percentile: u32) -> f64 {
+    if sorted_fee_rates.is_empty() {
+        return 0.0;
+    }
+    let rank = (percentile as f64 / 100.0 * sorted_fee_rates.len() as f64).ceil() as usize;
+    sorted_fee_rates[rank.clamp(1, sorted_fee_rates.len()) - 1]
+}
+
+#[cfg(feature = "server")]
+impl FeeStats {
+    pub fn from_transactions(transactions: &[UnconfirmedTxs], snapshot_time: u64) -> FeeStats {
+        let mut fee_rates: Vec<f64> = transactions
+            .iter()
+            .map(|transaction| transaction.fee_rate())
+            .collect();
+        fee_rates.sort_by(f64::total_cmp);
+
+        FeeStats {
+            transaction_count: transactions.len() as u32,
+            total_fees: transactions
+                .iter()
+                .map(|transaction| transaction.fee())
+                .sum(),
+            min_fee_rate: fee_rates.first().copied().unwrap_or_default(),
+            median_fee_rate: percentile(&fee_rates, 50),
+            max_fee_rate: fee_rates.last().copied().unwrap_or_default(),
+            percentiles: FEE_RATE_PERCENTILES
+                .iter()
+                .map(|&rank| FeePercentile {
+                    percentile: rank,
+                    fee_rate: percentile(&fee_rates, rank),
+                })
+                .collect(),
+            snapshot_time,
+        }
+    }
+}
+
+/* Fee rate that outbids every transaction left out of the next target_blocks blocks,
+ * assuming miners fill them by fee rate. Transaction costs are not part of the node's
+ * mempool listing, so blocks are only limited by size. 0 when the whole mempool fits. */
+#[cfg(feature = "server")]
+fn required_fee_rate(
+    fee_rates_and_sizes: &[(f64, u64)],
+    max_block_size: u64,
+    target_blocks: u32,
+) -> f64 {
+    let capacity = max_block_size * target_blocks as u64;
+    let mut used = 0;
+    for &(fee_rate, size) in fee_rates_and_sizes {
+        used += size;
+        if used > capacity {
+            return fee_rate + 1.0;
+        }
+    }
+    0.0
+}
+
+#[cfg(feature = "server")]
+impl FeeEstimate {
+    pub fn from_transactions(
+        transactions: &[UnconfirmedTxs],
+        transaction_size: u32,
+        max_block_size: u64,
+        snapshot_time: u64,
+    ) -> FeeEstimate {
+        let mut fee_rates_and_sizes: Vec<(f64, u64)> = transactions
+            .iter()
+            .map(|transaction| (transaction.fee_rate(), transaction.size as u64))
+            .collect();
+        fee_rates_and_sizes.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let recommendation = |target_blocks: u32| {
+            let fee_rate = required_fee_rate(&fee_rates_and_sizes, max_block_size, target_blocks);
+            let fee = ((fee_rate * transaction_size as f64).ceil() as u64).max(MIN_FEE);
+            FeeRecommendation {
+                target_blocks,
+                fee_rate: fee as f64 / transaction_size.max(1) as f64,
+                fee,
+            }
+        };
+
+        FeeEstimate {
+            transaction_size,
+            max_block_size,
+            fast: recommendation(FAST_TARGET_BLOCKS),
+            normal: recommendation(NORMAL_TARGET_BLOCKS),
+            slow: recommendation(SLOW_TARGET_BLOCKS),
+            snapshot_time,
+        }
+    }
+}
+
+/* Current block size limit voted by the miners */
+#[cfg(feature = "server")]
+pub async fn api_fetch_max_block_size() -> Result<u64, ServerFnError> {
+    let info = api_fetch_info().await?;
+    Ok(info["parameters"]["maxBlockSize"]
+        .as_u64()
+        .unwrap_or(DEFAULT_MAX_BLOCK_SIZE))
+}
+
+#[server(GetFeeStats)]
+pub async fn get_fee_stats() -> Result<FeeStats, ServerFnError> {
+    let data = crate::data::poller::latest_snapshot().await?;
+    Ok(FeeStats::from_transactions(
+        &data.unconfirmed_txs,
+        data.snapshot_time,
+    ))
+}
+
+/* transaction_size 0 stands for TYPICAL_TRANSACTION_SIZE */
+#[server(GetFeeEstimate)]
+pub async fn get_fee_estimate(transaction_size: u32) -> Result<FeeEstimate, ServerFnError> {
+    let data = crate::data::poller::latest_snapshot().await?;
+    let max_block_size = api_fetch_max_block_size().await?;
+    let transaction_size = match transaction_size {
+        0 => TYPICAL_TRANSACTION_SIZE,
+        transaction_size => transaction_size,
+    };
+
+    Ok(FeeEstimate::from_transactions(
+        &data.unconfirmed_txs,
+        transaction_size,
+        max_block_size,
+        data.snapshot_time,
+    ))
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let fee_rates: Vec<f64> = (1..=10).map(|rate| rate as f64 * 100.0).collect();
+        assert_eq!(percentile(&fee_rates, 10), 100.0);
+        assert_eq!(percentile(&fee_rates, 25), 300.0);
+        assert_eq!(percentile(&fee_rates, 50), 500.0);
+        assert_eq!(percentile(&fee_rates, 90), 900.0);
+        assert_eq!(percentile(&fee_rates, 100), 1000.0);
+        assert_eq!(percentile(&fee_rates, 0), 100.0);
+        assert_eq!(percentile(&[42.0], 75), 42.0);
+        assert_eq!(percentile(&[], 50), 0.0);
+    }
+
+    #[test]
+    fn fee_stats_summarize_the_snapshot() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("a", &["1"], 3_000_000, 1000),
+            UnconfirmedTxs::for_test("b", &["2"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("c", &["3"], 2_000_000, 500),
+            UnconfirmedTxs::for_test("d", &["4"], 1_000_000, 200),
+        ];
+        let stats = FeeStats::from_transactions(&transactions, 7);
+        assert_eq!(stats.transaction_count, 4);
+        assert_eq!(stats.total_fees, 7_000_000);
+        assert_eq!(stats.min_fee_rate, 1000.0);
+        assert_eq!(stats.median_fee_rate, 3000.0);
+        assert_eq!(stats.max_fee_rate, 5000.0);
+        assert_eq!(
+            stats
+                .percentiles
+                .iter()
+                .map(|percentile| (percentile.percentile, percentile.fee_rate))
+                .collect::<Vec<_>>(),
+            [(10, 1000.0), (25, 1000.0), (75, 4000.0), (90, 5000.0)]
+        );
+        assert_eq!(stats.snapshot_time, 7);
+
+        let stats = FeeStats::from_transactions(&[], 7);
+        assert_eq!(stats.transaction_count, 0);
+        assert_eq!(stats.median_fee_rate, 0.0);
+    }
+
+    #[test]
+    fn histogram_buckets_follow_the_bands() {
+        assert_eq!(fee_rate_bucket(0.0), 0);
+        assert_eq!(fee_rate_bucket(999.9), 0);
+        assert_eq!(fee_rate_bucket(1000.0), 1);
+        assert_eq!(fee_rate_bucket(1_000_000.0), FEE_RATE_BANDS.len() - 1);
+
+        let transactions = vec![
+            UnconfirmedTxs::for_test("a", &["1"], 500_000, 1000),
+            UnconfirmedTxs::for_test("b", &["2"], 1_500_000, 1000),
+            UnconfirmedTxs::for_test("c", &["3"], 1_200_000, 1000),
+            UnconfirmedTxs::for_test("d", &["4"], 100_000_000, 500),
+        ];
+        let buckets = fee_rate_histogram(&transactions);
+        assert_eq!(buckets.len(), FEE_RATE_BANDS.len());
+        assert_eq!(buckets[0].transaction_count, 1);
+        assert_eq!(buckets[1].transaction_count, 2);
+        assert_eq!(buckets[1].total_size, 2000);
+        assert_eq!(buckets[1].label(), "1000 - 2000");
+        let last = buckets.last().unwrap();
+        assert_eq!(last.transaction_count, 1);
+        assert_eq!(last.upper_bound, None);
+        assert_eq!(last.label(), ">= 50000");
+    }
+
+    #[test]
+    fn required_fee_rate_outbids_what_does_not_fit() {
+        /* Sorted by fee rate, highest first */
+        let fee_rates_and_sizes = [(5000.0, 600), (3000.0, 600), (1000.0, 600)];
+        assert_eq!(required_fee_rate(&fee_rates_and_sizes, 1000, 1), 3001.0);
+        assert_eq!(required_fee_rate(&fee_rates_and_sizes, 700, 2), 1001.0);
+        assert_eq!(required_fee_rate(&fee_rates_and_sizes, 1000, 2), 0.0);
+        assert_eq!(required_fee_rate(&[], 1000, 1), 0.0);
+    }
+
+    #[test]
+    fn fee_estimate_never_goes_below_the_minimum_fee() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("a", &["1"], 6_000_000, 600),
+            UnconfirmedTxs::for_test("b", &["2"], 3_000_000, 600),
+        ];
+        let estimate = FeeEstimate::from_transactions(&transactions, 250, 1000, 7);
+        assert_eq!(estimate.fast.target_blocks, 1);
+        assert_eq!(estimate.fast.fee, 1_250_250);
+        assert_eq!(estimate.fast.fee_rate, 5001.0);
+        /* Everything fits in 3 blocks, the minimum fee is enough */
+        assert_eq!(estimate.normal.fee, MIN_FEE);
+        assert_eq!(estimate.slow.fee, MIN_FEE);
+    }
+}
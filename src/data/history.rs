@@ -0,0 +1,186 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::data::{poller::now_millis, storage::storage};
+#[cfg(feature = "server")]
+use rusqlite::Connection;
+
+/* Points per series returned to the browser, snapshots are averaged into this many buckets */
+#[cfg(feature = "server")]
+const MAX_HISTORY_POINTS: u64 = 240;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HistoryWindow {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl HistoryWindow {
+    pub const ALL: [HistoryWindow; 4] = [
+        HistoryWindow::Hour,
+        HistoryWindow::Day,
+        HistoryWindow::Week,
+        HistoryWindow::Month,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistoryWindow::Hour => "1h",
+            HistoryWindow::Day => "24h",
+            HistoryWindow::Week => "7d",
+            HistoryWindow::Month => "30d",
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn duration_ms(&self) -> u64 {
+        const HOUR: u64 = 60 * 60 * 1000;
+        match self {
+            HistoryWindow::Hour => HOUR,
+            HistoryWindow::Day => 24 * HOUR,
+            HistoryWindow::Week => 7 * 24 * HOUR,
+            HistoryWindow::Month => 30 * 24 * HOUR,
+        }
+    }
+
+    #[cfg(feature = "server")]
+    fn bucket_ms(&self) -> u64 {
+        (self.duration_ms() / MAX_HISTORY_POINTS).max(1)
+    }
+}
+
+/* Averages over the snapshots of one bucket, timestamp is the start of the bucket */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    pub timestamp: u64,
+    pub transaction_count: f64,
+    pub total_size: f64,
+    /* nanoERG */
+    pub total_value: f64,
+    /* nanoERG per byte */
+    pub median_fee_rate: f64,
+}
+
+/* Snapshots taken between from and to, both included, averaged per bucket_ms wide bucket */
+#[cfg(feature = "server")]
+fn query_history(
+    connection: &Connection,
+    from: u64,
+    to: u64,
+    bucket_ms: u64,
+) -> rusqlite::Result<Vec<HistoryPoint>> {
+    let mut statement = connection.prepare(
+        "SELECT snapshot_time / ?1 * ?1, AVG(transaction_count), AVG(total_size),
+             AVG(total_value), AVG(median_fee_rate)
+         FROM snapshots
+         WHERE snapshot_time >= ?2 AND snapshot_time <= ?3
+         GROUP BY snapshot_time / ?1
+         ORDER BY 1",
+    )?;
+    let points = statement
+        .query_map(rusqlite::params![bucket_ms, from, to], |row| {
+            Ok(HistoryPoint {
+                timestamp: row.get(0)?,
+                transaction_count: row.get(1)?,
+                total_size: row.get(2)?,
+                total_value: row.get(3)?,
+                median_fee_rate: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<HistoryPoint>>>()?;
+    Ok(points)
+}
+
+/* Oldest first, empty when nothing was stored over the window */
+#[server(GetMempoolHistory)]
+pub async fn get_mempool_history(
+    window: HistoryWindow,
+) -> Result<Vec<HistoryPoint>, ServerFnError> {
+    let Some(storage) = storage() else {
+        return Ok(vec![]);
+    };
+    let to = now_millis();
+    let from = to.saturating_sub(window.duration_ms());
+    let bucket_ms = window.bucket_ms();
+
+    tokio::task::spawn_blocking(move || {
+        storage.query(|connection| query_history(connection, from, to, bucket_ms))
+    })
+    .await?
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use crate::data::{
+        data::{UnconfirmedTxs, VectorUnconfirmedTxs},
+        dropped::Departures,
+        storage::Storage,
+    };
+
+    /* A snapshot of count transactions of 200 bytes at 5000 nanoERG/byte */
+    fn persist_snapshot(storage: &Storage, snapshot_time: u64, count: usize) {
+        let transactions = (0..count)
+            .map(|index| {
+                UnconfirmedTxs::for_test(
+                    &format!("{}:{}", snapshot_time, index),
+                    &[],
+                    1_000_000,
+                    200,
+                )
+            })
+            .collect();
+        let mut snapshot = VectorUnconfirmedTxs::for_test(transactions, snapshot_time);
+        snapshot.total_size = 200 * count as u64;
+        storage
+            .persist_poll(Some(&snapshot), &[], &Departures::default(), snapshot_time)
+            .unwrap();
+    }
+
+    #[test]
+    fn averages_snapshots_per_bucket() {
+        let storage = Storage::for_test(0);
+        for (snapshot_time, count) in [
+            (9_999, 9),
+            (10_000, 2),
+            (10_999, 4),
+            (11_000, 1),
+            (12_999, 3),
+            (13_001, 9),
+        ] {
+            persist_snapshot(&storage, snapshot_time, count);
+        }
+
+        let points = storage
+            .query(|connection| query_history(connection, 10_000, 13_000, 1_000))
+            .unwrap();
+        let summary: Vec<(u64, f64, f64)> = points
+            .iter()
+            .map(|point| (point.timestamp, point.transaction_count, point.total_size))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (10_000, 3.0, 600.0),
+                (11_000, 1.0, 200.0),
+                (12_000, 3.0, 600.0),
+            ]
+        );
+        assert!(points.iter().all(|point| point.median_fee_rate == 5_000.0));
+    }
+
+    #[test]
+    fn splits_windows_into_at_most_max_history_points_buckets() {
+        for window in HistoryWindow::ALL {
+            assert_eq!(
+                window.bucket_ms() * MAX_HISTORY_POINTS,
+                window.duration_ms()
+            );
+        }
+        assert_eq!(HistoryWindow::Hour.bucket_ms(), 15_000);
+    }
+}
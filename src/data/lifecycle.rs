@@ -0,0 +1,318 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::fees::FeeRateBucket;
+
+#[cfg(feature = "server")]
+use crate::data::{
+    fees::{fee_rate_bucket, fee_rate_buckets},
+    storage::storage,
+};
+#[cfg(feature = "server")]
+use rusqlite::{Connection, OptionalExtension};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransactionOutcome {
+    Confirmed,
+    /* Left the mempool after another transaction spent one of its inputs */
+    DoubleSpent,
+    /* Left the mempool without being seen in a block */
+    Dropped,
+}
+
+/* What the poller saw of a transaction, times in unix milliseconds */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionLifecycle {
+    pub first_seen: u64,
+    pub last_seen: u64,
+    /* Set once the transaction is no longer in the mempool */
+    pub left_at: Option<u64>,
+    pub outcome: Option<TransactionOutcome>,
+    pub confirmed_height: Option<u32>,
+    /* Timestamp of the including block */
+    pub confirmed_at: Option<u64>,
+}
+
+impl TransactionLifecycle {
+    /* From first seen to the including block, or to the last sighting while unconfirmed */
+    pub fn time_in_mempool(&self) -> u64 {
+        self.confirmed_at
+            .or(self.left_at)
+            .unwrap_or(self.last_seen)
+            .saturating_sub(self.first_seen)
+    }
+}
+
+/* How long confirmed transactions of a fee rate bucket waited, in milliseconds */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmationTimes {
+    /* transaction_count and total_size count the confirmed transactions */
+    pub bucket: FeeRateBucket,
+    pub median_time: u64,
+    pub average_time: u64,
+}
+
+#[cfg(feature = "server")]
+fn outcome(value: Option<String>) -> Option<TransactionOutcome> {
+    match value.as_deref() {
+        Some("confirmed") => Some(TransactionOutcome::Confirmed),
+        Some("double_spent") => Some(TransactionOutcome::DoubleSpent),
+        Some("dropped") => Some(TransactionOutcome::Dropped),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "server")]
+fn query_lifecycle(
+    connection: &Connection,
+    tx_id: &str,
+) -> rusqlite::Result<Option<TransactionLifecycle>> {
+    connection
+        .query_row(
+            "SELECT mempool_transactions.first_seen, mempool_transactions.last_seen,
+                 mempool_transactions.left_at, mempool_transactions.outcome,
+                 mempool_transactions.confirmed_height, blocks.timestamp
+             FROM mempool_transactions
+             LEFT JOIN blocks ON blocks.height = mempool_transactions.confirmed_height
+             WHERE mempool_transactions.tx_id = ?1",
+            [tx_id],
+            |row| {
+                Ok(TransactionLifecycle {
+                    first_seen: row.get(0)?,
+                    last_seen: row.get(1)?,
+                    left_at: row.get(2)?,
+                    outcome: outcome(row.get(3)?),
+                    confirmed_height: row.get(4)?,
+                    confirmed_at: row.get(5)?,
+                })
+            },
+        )
+        .optional()
+}
+
+/* Fee rate, size and time from first seen to the including block of every confirmed row */
+#[cfg(feature = "server")]
+fn query_confirmed(connection: &Connection) -> rusqlite::Result<Vec<(f64, u64, u64)>> {
+    let mut statement = connection.prepare(
+        "SELECT mempool_transactions.fee_rate, mempool_transactions.size,
+             MAX(blocks.timestamp - mempool_transactions.first_seen, 0)
+         FROM mempool_transactions
+         JOIN blocks ON blocks.height = mempool_transactions.confirmed_height
+         WHERE mempool_transactions.outcome = 'confirmed'",
+    )?;
+    let rows = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<Vec<(f64, u64, u64)>>>()?;
+    Ok(rows)
+}
+
+#[cfg(feature = "server")]
+fn confirmation_times(confirmed: Vec<(f64, u64, u64)>) -> Vec<ConfirmationTimes> {
+    let mut times: Vec<Vec<u64>> = vec![vec![]; fee_rate_buckets().len()];
+    let mut buckets = fee_rate_buckets();
+    for (fee_rate, size, time) in confirmed {
+        let index = fee_rate_bucket(fee_rate);
+        buckets[index].transaction_count += 1;
+        buckets[index].total_size += size;
+        times[index].push(time);
+    }
+
+    buckets
+        .into_iter()
+        .zip(times)
+        .map(|(bucket, mut times)| {
+            times.sort_unstable();
+            ConfirmationTimes {
+                median_time: times.get(times.len() / 2).copied().unwrap_or_default(),
+                average_time: match times.len() {
+                    0 => 0,
+                    count => times.iter().sum::<u64>() / count as u64,
+                },
+                bucket,
+            }
+        })
+        .collect()
+}
+
+/* None when the transaction was never seen in the mempool by this server */
+#[cfg(feature = "server")]
+pub async fn api_fetch_lifecycle(
+    tx_id: &str,
+) -> Result<Option<TransactionLifecycle>, ServerFnError> {
+    let Some(storage) = storage() else {
+        return Ok(None);
+    };
+    let tx_id = tx_id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        storage.query(|connection| query_lifecycle(connection, &tx_id))
+    })
+    .await?
+}
+
+/* Over every confirmed transaction still within the storage retention, lowest fee rate first */
+#[server(GetConfirmationTimes)]
+pub async fn get_confirmation_times() -> Result<Vec<ConfirmationTimes>, ServerFnError> {
+    let Some(storage) = storage() else {
+        return Ok(vec![]);
+    };
+
+    let confirmed = tokio::task::spawn_blocking(move || storage.query(query_confirmed)).await??;
+    Ok(confirmation_times(confirmed))
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use crate::data::{
+        data::{UnconfirmedTxs, VectorUnconfirmedTxs},
+        dropped::{Departures, DroppedTransaction},
+        storage::Storage,
+        stream::ConfirmedBlock,
+    };
+
+    fn snapshot(transactions: Vec<UnconfirmedTxs>, snapshot_time: u64) -> VectorUnconfirmedTxs {
+        VectorUnconfirmedTxs::for_test(transactions, snapshot_time)
+    }
+
+    fn block(height: u32, timestamp: u64, tx_ids: &[&str]) -> ConfirmedBlock {
+        ConfirmedBlock {
+            height,
+            timestamp,
+            transaction_ids: tx_ids.iter().map(|tx_id| tx_id.to_string()).collect(),
+            ..ConfirmedBlock::default()
+        }
+    }
+
+    fn lifecycle(storage: &Storage, tx_id: &str) -> Option<TransactionLifecycle> {
+        storage
+            .query(|connection| query_lifecycle(connection, tx_id))
+            .unwrap()
+    }
+
+    #[test]
+    fn tracks_first_seen_and_confirmation() {
+        let storage = Storage::for_test(0);
+        /* 1000 nanoERG/byte, in the 1000 - 2000 bucket */
+        let transaction = UnconfirmedTxs::for_test("a", &[], 200_000, 200);
+        storage
+            .persist_poll(
+                Some(&snapshot(vec![transaction.clone()], 1_000)),
+                &[],
+                &Departures::default(),
+                1_000,
+            )
+            .unwrap();
+        storage
+            .persist_poll(
+                Some(&snapshot(vec![transaction], 2_000)),
+                &[],
+                &Departures::default(),
+                2_000,
+            )
+            .unwrap();
+        assert_eq!(
+            lifecycle(&storage, "a"),
+            Some(TransactionLifecycle {
+                first_seen: 1_000,
+                last_seen: 2_000,
+                ..TransactionLifecycle::default()
+            })
+        );
+
+        let departures = Departures {
+            confirmed: vec!["a".to_string()],
+            ..Departures::default()
+        };
+        storage
+            .persist_poll(
+                Some(&snapshot(vec![], 3_000)),
+                &[block(10, 2_500, &["a"])],
+                &departures,
+                3_000,
+            )
+            .unwrap();
+
+        let confirmed = lifecycle(&storage, "a").unwrap();
+        assert_eq!(
+            confirmed,
+            TransactionLifecycle {
+                first_seen: 1_000,
+                last_seen: 2_000,
+                left_at: Some(3_000),
+                outcome: Some(TransactionOutcome::Confirmed),
+                confirmed_height: Some(10),
+                confirmed_at: Some(2_500),
+            }
+        );
+        assert_eq!(confirmed.time_in_mempool(), 1_500);
+        assert!(lifecycle(&storage, "unknown").is_none());
+
+        let times = confirmation_times(storage.query(query_confirmed).unwrap());
+        let bucket = &times[fee_rate_bucket(1_000.0)];
+        assert_eq!(bucket.bucket.transaction_count, 1);
+        assert_eq!((bucket.median_time, bucket.average_time), (1_500, 1_500));
+        assert_eq!(
+            times
+                .iter()
+                .map(|times| times.bucket.transaction_count)
+                .sum::<u32>(),
+            1
+        );
+    }
+
+    #[test]
+    fn corrects_dropped_transactions_found_in_a_block() {
+        let storage = Storage::for_test(0);
+        storage
+            .persist_poll(
+                Some(&snapshot(
+                    vec![UnconfirmedTxs::for_test("a", &[], 200_000, 200)],
+                    1_000,
+                )),
+                &[],
+                &Departures::default(),
+                1_000,
+            )
+            .unwrap();
+
+        let departures = Departures {
+            dropped: vec![DroppedTransaction {
+                tx_id: "a".to_string(),
+                size: 200,
+                fee: 200_000,
+                fee_rate: 1_000.0,
+                last_seen: 1_000,
+                left_at: 2_000,
+                outcome: TransactionOutcome::Dropped,
+            }],
+            ..Departures::default()
+        };
+        storage
+            .persist_poll(Some(&snapshot(vec![], 2_000)), &[], &departures, 2_000)
+            .unwrap();
+        assert_eq!(
+            lifecycle(&storage, "a").unwrap().outcome,
+            Some(TransactionOutcome::Dropped)
+        );
+        assert!(storage.query(query_confirmed).unwrap().is_empty());
+
+        /* Seen in a block mined before the node caught up */
+        storage
+            .persist_poll(
+                None,
+                &[block(10, 1_800, &["a"])],
+                &Departures::default(),
+                4_000,
+            )
+            .unwrap();
+        let corrected = lifecycle(&storage, "a").unwrap();
+        assert_eq!(corrected.outcome, Some(TransactionOutcome::Confirmed));
+        assert_eq!(corrected.left_at, Some(4_000));
+        assert_eq!(corrected.confirmed_at, Some(1_800));
+        assert_eq!(
+            storage.query(query_confirmed).unwrap(),
+            vec![(1_000.0, 200, 800)]
+        );
+    }
+}
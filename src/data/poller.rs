@@ -0,0 +1,282 @@
+use std::{
+    sync::{Arc, OnceLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use dioxus::prelude::ServerFnError;
+use dioxus_logger::tracing;
+use tokio::{
+    sync::watch,
+    time::{self, MissedTickBehavior},
+};
+
+use crate::data::{
+    config::node,
+    conflicts::{track_double_spends, DoubleSpend},
+    data::{api_fetch_info, Block, VectorUnconfirmedTxs},
+    dropped::{classify_departures, track_dropped, Departures, DroppedTransaction},
+    storage::storage,
+    stream::{ConfirmedBlock, MAX_RECENT_BLOCKS},
+};
+
+/* Latest state of the background mempool poll, shared by every server function */
+#[derive(Debug, Clone, Default)]
+pub struct PollerState {
+    /* Last successful snapshot, kept while later polls fail */
+    pub snapshot: Option<Arc<VectorUnconfirmedTxs>>,
+    pub last_error: Option<String>,
+    /* Height of the best full block seen by the poller */
+    pub best_height: u32,
+    /* best_height when the snapshot was taken */
+    pub snapshot_height: u32,
+    /* Blocks seen since start-up, oldest first, at most MAX_RECENT_BLOCKS */
+    pub recent_blocks: Vec<ConfirmedBlock>,
+    /* Conflicting mempool transactions seen since start-up, newest first */
+    pub double_spends: Vec<DoubleSpend>,
+    /* Transactions that left the mempool unconfirmed, newest first */
+    pub dropped: Vec<DroppedTransaction>,
+}
+
+static POLLER: OnceLock<watch::Sender<PollerState>> = OnceLock::new();
+
+/* Start the poller on first use, must be called from within the server's tokio runtime */
+pub fn poller() -> &'static watch::Sender<PollerState> {
+    POLLER.get_or_init(|| {
+        let (sender, _) = watch::channel(PollerState::default());
+        tokio::spawn(poll_mempool());
+        sender
+    })
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+async fn poll_mempool() {
+    let mut interval = time::interval(Duration::from_secs(node().config.poll_interval_secs));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        /* Blocks are polled after the mempool so that transactions leaving the mempool
+         * for a new block are seen together with that block */
+        let mut data = VectorUnconfirmedTxs::default().await;
+        let result = data.get_data().await.map(|()| {
+            data.snapshot_time = now_millis();
+            Arc::new(data)
+        });
+        let best_height = poller().borrow().best_height;
+        let new_blocks = poll_blocks(best_height).await;
+        let departures = match (&result, &new_blocks) {
+            (Ok(current), Ok(new_blocks)) => {
+                departures_since_snapshot(&poller().borrow(), current, new_blocks)
+            }
+            /* Without the new blocks a confirmed transaction would look dropped */
+            _ => None,
+        }
+        .unwrap_or_default();
+
+        if let Some(storage) = storage() {
+            let snapshot = result.as_ref().ok().cloned();
+            let blocks = new_blocks.as_ref().cloned().unwrap_or_default();
+            let departures = departures.clone();
+            let persisted = tokio::task::spawn_blocking(move || {
+                storage.persist_poll(snapshot.as_deref(), &blocks, &departures, now_millis())
+            })
+            .await;
+            match persisted {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => tracing::warn!("Failed to persist poll: {}", err),
+                Err(err) => tracing::warn!("Failed to persist poll: {}", err),
+            }
+        }
+
+        let snapshot_taken = result.is_ok();
+        poller().send_modify(|state| {
+            match result {
+                Ok(snapshot) => {
+                    track_double_spends(
+                        &mut state.double_spends,
+                        &snapshot.unconfirmed_txs,
+                        snapshot.snapshot_time,
+                    );
+                    state.snapshot = Some(snapshot);
+                    state.last_error = None;
+                }
+                Err(err) => {
+                    tracing::warn!("Mempool poll failed: {}", err);
+                    state.last_error = Some(err.to_string());
+                }
+            }
+            match new_blocks {
+                Ok(new_blocks) => {
+                    if let Some(block) = new_blocks.last() {
+                        state.best_height = block.height;
+                    }
+                    track_dropped(&mut state.dropped, departures.dropped, &new_blocks);
+                    state.recent_blocks.extend(new_blocks);
+                    let overflow = state.recent_blocks.len().saturating_sub(MAX_RECENT_BLOCKS);
+                    state.recent_blocks.drain(..overflow);
+                }
+                Err(err) => tracing::warn!("Block poll failed: {}", err),
+            }
+            if snapshot_taken {
+                state.snapshot_height = state.best_height;
+            }
+        });
+    }
+}
+
+/* Transactions that left the mempool since the previous snapshot. None when that
+ * can't be told: without a previous snapshot, when the current one stops at the fetch cap, or
+ * when a block found since the previous snapshot is no longer kept. */
+fn departures_since_snapshot(
+    state: &PollerState,
+    current: &VectorUnconfirmedTxs,
+    new_blocks: &[ConfirmedBlock],
+) -> Option<Departures> {
+    let previous = state.snapshot.as_ref()?;
+    if current.mempool_size as usize > current.unconfirmed_txs.len() {
+        return None;
+    }
+
+    /* Blocks found while mempool polls failed are in recent_blocks already */
+    let blocks: Vec<&ConfirmedBlock> = state
+        .recent_blocks
+        .iter()
+        .chain(new_blocks)
+        .filter(|block| block.height > state.snapshot_height)
+        .collect();
+    let tip_height = blocks
+        .last()
+        .map(|block| block.height)
+        .unwrap_or(state.snapshot_height);
+    if blocks.len() != (tip_height - state.snapshot_height) as usize {
+        return None;
+    }
+
+    Some(classify_departures(
+        previous,
+        current,
+        &blocks,
+        &state.double_spends,
+    ))
+}
+
+/* Fetch the blocks above known_height, only the chain tip when nothing is known yet */
+async fn poll_blocks(known_height: u32) -> Result<Vec<ConfirmedBlock>, ServerFnError> {
+    let info = api_fetch_info().await?;
+    let full_height = info["fullHeight"].as_u64().unwrap_or_default() as u32;
+
+    let from_height = match known_height {
+        0 => full_height,
+        known_height => {
+            (known_height + 1).max(full_height.saturating_sub(MAX_RECENT_BLOCKS as u32 - 1))
+        }
+    };
+
+    let mut new_blocks = vec![];
+    for height in from_height..=full_height {
+        /* The first id at a height is the one on the node's best chain */
+        let Some(block_id) = Block::api_fetch_block_ids(height).await?.into_iter().next() else {
+            break;
+        };
+        let block = Block::api_fetch_block(&block_id).await?;
+        new_blocks.push(ConfirmedBlock {
+            height: block.header.height,
+            id: block.header.id,
+            timestamp: block.header.timestamp,
+            transaction_ids: block
+                .transactions
+                .iter()
+                .map(|transaction| transaction.id.clone())
+                .collect(),
+            spent_box_ids: block
+                .transactions
+                .into_iter()
+                .flat_map(|transaction| transaction.inputs)
+                .map(|input| input.box_id)
+                .collect(),
+        });
+    }
+    Ok(new_blocks)
+}
+
+/* The cached mempool snapshot, waits for the first poll to finish after start-up */
+pub async fn latest_snapshot() -> Result<Arc<VectorUnconfirmedTxs>, ServerFnError> {
+    let mut receiver = poller().subscribe();
+    let state = receiver
+        .wait_for(|state| state.snapshot.is_some() || state.last_error.is_some())
+        .await
+        .map_err(ServerFnError::new)?;
+
+    match (&state.snapshot, &state.last_error) {
+        (Some(snapshot), _) => Ok(snapshot.clone()),
+        (None, Some(err)) => Err(ServerFnError::new(err)),
+        (None, None) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::data::UnconfirmedTxs;
+
+    fn block(height: u32, transaction_ids: &[&str]) -> ConfirmedBlock {
+        ConfirmedBlock {
+            height,
+            transaction_ids: transaction_ids.iter().map(|id| id.to_string()).collect(),
+            ..ConfirmedBlock::default()
+        }
+    }
+
+    /* a and b in the previous snapshot, taken at height 10 */
+    fn state(recent_blocks: Vec<ConfirmedBlock>) -> PollerState {
+        PollerState {
+            snapshot: Some(Arc::new(VectorUnconfirmedTxs::for_test(
+                vec![
+                    UnconfirmedTxs::for_test("a", &["box_a"], 1_000_000, 200),
+                    UnconfirmedTxs::for_test("b", &["box_b"], 1_000_000, 200),
+                ],
+                1_000,
+            ))),
+            snapshot_height: 10,
+            recent_blocks,
+            ..PollerState::default()
+        }
+    }
+
+    #[test]
+    fn classifies_against_every_block_since_the_snapshot() {
+        /* Block 11 was seen while mempool polls failed, block 12 on this tick */
+        let state = state(vec![block(10, &["old"]), block(11, &["a"])]);
+        let current = VectorUnconfirmedTxs::for_test(vec![], 2_000);
+
+        let departures = departures_since_snapshot(&state, &current, &[block(12, &["x"])]).unwrap();
+        assert_eq!(departures.confirmed, vec!["a"]);
+        assert_eq!(departures.dropped.len(), 1);
+        assert_eq!(departures.dropped[0].tx_id, "b");
+    }
+
+    #[test]
+    fn skips_capped_snapshots() {
+        let state = state(vec![]);
+        let mut current = VectorUnconfirmedTxs::for_test(vec![], 2_000);
+        current.mempool_size = 1;
+
+        assert!(departures_since_snapshot(&state, &current, &[]).is_none());
+    }
+
+    #[test]
+    fn skips_when_blocks_are_missing() {
+        let state = state(vec![]);
+        let current = VectorUnconfirmedTxs::for_test(vec![], 2_000);
+
+        assert!(departures_since_snapshot(&state, &current, &[block(12, &["a"])]).is_none());
+        assert!(departures_since_snapshot(&PollerState::default(), &current, &[]).is_none());
+    }
+}
@@ -0,0 +1,251 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Route;
+
+#[cfg(feature = "server")]
+use crate::{
+    data::{
+        data::{
+            api_fetch_info, api_fetch_optional, is_node_id, NODE_BLOCKCHAIN_BOX_BY_ID,
+            NODE_BLOCKCHAIN_TOKEN_BY_ID, NODE_BLOCKCHAIN_TRANSACTION_BY_ID, NODE_BLOCKS,
+            NODE_UNCONFIRMED_BY_TRANSACTION_ID, NODE_UTXO_WITH_POOL_BY_ID,
+        },
+        poller::poller,
+    },
+    utils::address::is_valid_address,
+};
+
+/* Shortest hex prefix matched against mempool and recent block ids */
+#[cfg(feature = "server")]
+const MIN_ID_PREFIX_LENGTH: usize = 4;
+
+#[cfg(feature = "server")]
+const MAX_SUGGESTIONS: usize = 8;
+
+/* Something a search query resolved to */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SearchMatch {
+    Block { height: u32 },
+    Transaction { tx_id: String },
+    Box { box_id: String },
+    Token { token_id: String },
+    Address { address: String },
+}
+
+impl SearchMatch {
+    /* Page showing the match */
+    pub(crate) fn route(&self) -> Route {
+        match self {
+            SearchMatch::Block { height } => Route::BlockVisualizerWrapper {
+                block_height: *height,
+            },
+            SearchMatch::Transaction { tx_id } => Route::TransactionWrapper {
+                tx_id: tx_id.clone(),
+            },
+            SearchMatch::Box { box_id } => Route::BoxWrapper {
+                box_id: box_id.clone(),
+            },
+            SearchMatch::Address { address } => Route::AddressWrapper {
+                address: address.clone(),
+            },
+            SearchMatch::Token { token_id } => Route::TokenWrapper {
+                token_id: token_id.clone(),
+            },
+        }
+    }
+
+    /* The query that resolves to this match */
+    pub fn query(&self) -> String {
+        match self {
+            SearchMatch::Block { height } => height.to_string(),
+            SearchMatch::Transaction { tx_id } => tx_id.clone(),
+            SearchMatch::Box { box_id } => box_id.clone(),
+            SearchMatch::Token { token_id } => token_id.clone(),
+            SearchMatch::Address { address } => address.clone(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let kind = match self {
+            SearchMatch::Block { .. } => "BLOCK",
+            SearchMatch::Transaction { .. } => "TRANSACTION",
+            SearchMatch::Box { .. } => "BOX",
+            SearchMatch::Token { .. } => "TOKEN",
+            SearchMatch::Address { .. } => "ADDRESS",
+        };
+        format!("{}: {}", kind, self.query())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResults {
+    /* More than one match when an id is shared, e.g. a token id and the box it was minted from */
+    pub matches: Vec<SearchMatch>,
+    /* Only filled when nothing matched */
+    pub suggestions: Vec<SearchMatch>,
+}
+
+#[cfg(feature = "server")]
+impl SearchResults {
+    /* Classify the query as a block height, a 64 character hex id or an address */
+    pub async fn api_search(query: &str) -> Result<SearchResults, ServerFnError> {
+        let query = query.trim();
+
+        let matches = if !query.is_empty() && query.bytes().all(|b| b.is_ascii_digit()) {
+            Self::api_search_height(query).await?
+        } else if is_node_id(query) {
+            Self::api_search_id(&query.to_ascii_lowercase()).await?
+        } else if is_valid_address(query) {
+            vec![SearchMatch::Address {
+                address: query.to_string(),
+            }]
+        } else {
+            vec![]
+        };
+
+        let suggestions = match matches.is_empty() {
+            true => suggestions(query),
+            false => vec![],
+        };
+        Ok(SearchResults {
+            matches,
+            suggestions,
+        })
+    }
+
+    async fn api_search_height(query: &str) -> Result<Vec<SearchMatch>, ServerFnError> {
+        let Ok(height) = query.parse::<u32>() else {
+            return Ok(vec![]);
+        };
+        if height == 0 || height > best_height().await? {
+            return Ok(vec![]);
+        }
+        Ok(vec![SearchMatch::Block { height }])
+    }
+
+    /* The same id can name a transaction, a box, a block or a token, so every kind is looked up */
+    async fn api_search_id(id: &str) -> Result<Vec<SearchMatch>, ServerFnError> {
+        let in_mempool = poller().borrow().snapshot.as_ref().is_some_and(|snapshot| {
+            snapshot
+                .unconfirmed_txs
+                .iter()
+                .any(|transaction| transaction.id == id)
+        });
+
+        let (unconfirmed_tx, confirmed_tx, unspent_box, spent_box, header, token) = futures::join!(
+            api_fetch_optional(format!("{}{}", NODE_UNCONFIRMED_BY_TRANSACTION_ID, id)),
+            api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_TRANSACTION_BY_ID, id)),
+            api_fetch_optional(format!("{}{}", NODE_UTXO_WITH_POOL_BY_ID, id)),
+            api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_BOX_BY_ID, id)),
+            api_fetch_optional(format!("{}{}/header", NODE_BLOCKS, id)),
+            api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_TOKEN_BY_ID, id)),
+        );
+
+        let mut matches = vec![];
+        if in_mempool || unconfirmed_tx?.is_some() || confirmed_tx?.is_some() {
+            matches.push(SearchMatch::Transaction {
+                tx_id: id.to_string(),
+            });
+        }
+        if unspent_box?.is_some() || spent_box?.is_some() {
+            matches.push(SearchMatch::Box {
+                box_id: id.to_string(),
+            });
+        }
+        if let Some(header) = header? {
+            matches.push(SearchMatch::Block {
+                height: header["height"].as_u64().unwrap_or_default() as u32,
+            });
+        }
+        if token?.is_some() {
+            matches.push(SearchMatch::Token {
+                token_id: id.to_string(),
+            });
+        }
+        Ok(matches)
+    }
+}
+
+#[cfg(feature = "server")]
+async fn best_height() -> Result<u32, ServerFnError> {
+    let best_height = poller().borrow().best_height;
+    if best_height > 0 {
+        return Ok(best_height);
+    }
+    let info = api_fetch_info().await?;
+    Ok(info["fullHeight"].as_u64().unwrap_or_default() as u32)
+}
+
+/* Partial matches against the mempool and the recent blocks known to the poller */
+#[cfg(feature = "server")]
+fn suggestions(query: &str) -> Vec<SearchMatch> {
+    let query = query.trim().to_ascii_lowercase();
+    let state = poller().borrow();
+    let mut suggestions = vec![];
+
+    if !query.is_empty() && query.bytes().all(|b| b.is_ascii_digit()) {
+        suggestions.extend(
+            state
+                .recent_blocks
+                .iter()
+                .rev()
+                .filter(|block| block.height.to_string().starts_with(&query))
+                .map(|block| SearchMatch::Block {
+                    height: block.height,
+                }),
+        );
+        /* Heights past the chain tip suggest the tip */
+        if suggestions.is_empty() && state.best_height > 0 {
+            suggestions.push(SearchMatch::Block {
+                height: state.best_height,
+            });
+        }
+    } else if query.len() >= MIN_ID_PREFIX_LENGTH && query.bytes().all(|b| b.is_ascii_hexdigit()) {
+        suggestions.extend(
+            state
+                .recent_blocks
+                .iter()
+                .rev()
+                .filter(|block| block.id.starts_with(&query))
+                .map(|block| SearchMatch::Block {
+                    height: block.height,
+                }),
+        );
+        if let Some(snapshot) = &state.snapshot {
+            for transaction in snapshot.unconfirmed_txs.iter() {
+                if transaction.id.starts_with(&query) {
+                    suggestions.push(SearchMatch::Transaction {
+                        tx_id: transaction.id.clone(),
+                    });
+                }
+                suggestions.extend(
+                    transaction
+                        .outputs
+                        .iter()
+                        .filter(|output| output.box_id.starts_with(&query))
+                        .map(|output| SearchMatch::Box {
+                            box_id: output.box_id.clone(),
+                        }),
+                );
+                if suggestions.len() >= MAX_SUGGESTIONS {
+                    break;
+                }
+            }
+        }
+    }
+
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+#[server(Search)]
+pub async fn search(query: String) -> Result<SearchResults, ServerFnError> {
+    SearchResults::api_search(&query).await
+}
+
+/* Cheap completions for the search box, answered from the poller state only */
+#[server(GetSearchSuggestions)]
+pub async fn get_search_suggestions(query: String) -> Result<Vec<SearchMatch>, ServerFnError> {
+    Ok(suggestions(&query))
+}
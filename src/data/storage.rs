@@ -0,0 +1,445 @@
+use std::sync::{Mutex, OnceLock};
+
+use dioxus::prelude::ServerFnError;
+use dioxus_logger::tracing;
+use rusqlite::{params, Connection};
+
+use crate::data::{
+    config::StorageConfig, data::VectorUnconfirmedTxs, dropped::Departures, fees::FeeStats,
+    lifecycle::TransactionOutcome, stream::ConfirmedBlock,
+};
+
+static STORAGE: OnceLock<Storage> = OnceLock::new();
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/* Schema changes in the order they were introduced, never edit one that has shipped.
+ * The database's user_version is the number of migrations already applied. */
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE snapshots (
+        snapshot_time INTEGER PRIMARY KEY,
+        mempool_size INTEGER NOT NULL,
+        transaction_count INTEGER NOT NULL,
+        total_size INTEGER NOT NULL,
+        total_value INTEGER NOT NULL,
+        total_fees INTEGER NOT NULL,
+        median_fee_rate REAL NOT NULL
+    );
+
+    CREATE TABLE mempool_transactions (
+        tx_id TEXT PRIMARY KEY,
+        first_seen INTEGER NOT NULL,
+        last_seen INTEGER NOT NULL,
+        size INTEGER NOT NULL,
+        fee INTEGER NOT NULL,
+        fee_rate REAL NOT NULL
+    );
+    CREATE INDEX mempool_transactions_last_seen ON mempool_transactions (last_seen);
+
+    CREATE TABLE blocks (
+        height INTEGER PRIMARY KEY,
+        id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        transaction_count INTEGER NOT NULL
+    );
+    CREATE INDEX blocks_timestamp ON blocks (timestamp);
+
+    CREATE TABLE block_transactions (
+        tx_id TEXT PRIMARY KEY,
+        height INTEGER NOT NULL
+    );
+    CREATE INDEX block_transactions_height ON block_transactions (height);
+",
+    "
+    ALTER TABLE mempool_transactions ADD COLUMN left_at INTEGER;
+    ALTER TABLE mempool_transactions ADD COLUMN outcome TEXT;
+    ALTER TABLE mempool_transactions ADD COLUMN confirmed_height INTEGER;
+    CREATE INDEX mempool_transactions_outcome ON mempool_transactions (outcome);
+",
+];
+
+/* Mempool snapshots and confirmed blocks, one row per poll and per block */
+pub struct Storage {
+    connection: Mutex<Connection>,
+    retention_days: u64,
+}
+
+impl Storage {
+    fn open(config: &StorageConfig) -> Result<Storage, String> {
+        let mut connection = Connection::open(&config.path)
+            .map_err(|err| format!("failed to open database {}: {}", config.path, err))?;
+        migrate(&mut connection)
+            .map_err(|err| format!("failed to migrate database {}: {}", config.path, err))?;
+
+        Ok(Storage {
+            connection: Mutex::new(connection),
+            retention_days: config.retention_days,
+        })
+    }
+
+    /* Run a read-only query, blocking, so call it from spawn_blocking */
+    pub fn query<T>(
+        &self,
+        query: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, ServerFnError> {
+        let connection = self.connection.lock().unwrap();
+        query(&connection).map_err(ServerFnError::new)
+    }
+
+    /* Store one poll of the mempool and the blocks found with it, then apply retention */
+    pub fn persist_poll(
+        &self,
+        snapshot: Option<&VectorUnconfirmedTxs>,
+        blocks: &[ConfirmedBlock],
+        departures: &Departures,
+        now: u64,
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+
+        if let Some(snapshot) = snapshot {
+            let fee_stats =
+                FeeStats::from_transactions(&snapshot.unconfirmed_txs, snapshot.snapshot_time);
+            transaction.execute(
+                "INSERT OR REPLACE INTO snapshots (snapshot_time, mempool_size, transaction_count,
+                     total_size, total_value, total_fees, median_fee_rate)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    snapshot.snapshot_time,
+                    snapshot.mempool_size,
+                    fee_stats.transaction_count,
+                    snapshot.total_size,
+                    snapshot.total_value,
+                    fee_stats.total_fees,
+                    fee_stats.median_fee_rate,
+                ],
+            )?;
+
+            let mut upsert = transaction.prepare(
+                "INSERT INTO mempool_transactions (tx_id, first_seen, last_seen, size, fee, fee_rate)
+                 VALUES (?1, ?2, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (tx_id) DO UPDATE SET last_seen = excluded.last_seen,
+                     left_at = CASE WHEN outcome = 'confirmed' THEN left_at END,
+                     outcome = CASE WHEN outcome = 'confirmed' THEN outcome END",
+            )?;
+            for unconfirmed in snapshot.unconfirmed_txs.iter() {
+                upsert.execute(params![
+                    unconfirmed.id,
+                    snapshot.snapshot_time,
+                    unconfirmed.size,
+                    unconfirmed.fee(),
+                    unconfirmed.fee_rate(),
+                ])?;
+            }
+
+            /* Only the departures the poller could classify are closed, a transaction missing
+             * from a capped or partial snapshot stays open until it shows up again or in a block */
+            let mut close = transaction.prepare(
+                "UPDATE mempool_transactions SET left_at = ?2, outcome = ?3
+                 WHERE tx_id = ?1 AND outcome IS NOT 'confirmed'",
+            )?;
+            for tx_id in departures.confirmed.iter() {
+                close.execute(params![tx_id, snapshot.snapshot_time, "confirmed"])?;
+            }
+            for dropped in departures.dropped.iter() {
+                let outcome = match dropped.outcome {
+                    TransactionOutcome::DoubleSpent => "double_spent",
+                    TransactionOutcome::Confirmed | TransactionOutcome::Dropped => "dropped",
+                };
+                close.execute(params![dropped.tx_id, dropped.left_at, outcome])?;
+            }
+        }
+
+        /* A block at an already stored height replaces the one orphaned by a fork */
+        for block in blocks {
+            transaction.execute(
+                "DELETE FROM block_transactions WHERE height = ?1",
+                params![block.height],
+            )?;
+            transaction.execute(
+                "INSERT OR REPLACE INTO blocks (height, id, timestamp, transaction_count)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    block.height,
+                    block.id,
+                    block.timestamp,
+                    block.transaction_ids.len()
+                ],
+            )?;
+            let mut insert = transaction.prepare(
+                "INSERT OR REPLACE INTO block_transactions (tx_id, height) VALUES (?1, ?2)",
+            )?;
+            /* Also corrects a transaction closed as dropped that made it into a block after all */
+            let mut confirm = transaction.prepare(
+                "UPDATE mempool_transactions
+                 SET confirmed_height = ?2,
+                     left_at = CASE WHEN outcome = 'confirmed' THEN COALESCE(left_at, ?3) ELSE ?3 END,
+                     outcome = 'confirmed'
+                 WHERE tx_id = ?1",
+            )?;
+            for tx_id in block.transaction_ids.iter() {
+                insert.execute(params![tx_id, block.height])?;
+                confirm.execute(params![tx_id, block.height, now])?;
+            }
+        }
+
+        if self.retention_days > 0 {
+            let cutoff = now.saturating_sub(self.retention_days * MILLIS_PER_DAY);
+            transaction.execute(
+                "DELETE FROM snapshots WHERE snapshot_time < ?1",
+                params![cutoff],
+            )?;
+            transaction.execute(
+                "DELETE FROM mempool_transactions WHERE last_seen < ?1",
+                params![cutoff],
+            )?;
+            transaction.execute(
+                "DELETE FROM block_transactions WHERE height IN
+                     (SELECT height FROM blocks WHERE timestamp < ?1)",
+                params![cutoff],
+            )?;
+            transaction.execute("DELETE FROM blocks WHERE timestamp < ?1", params![cutoff])?;
+        }
+
+        transaction.commit()
+    }
+}
+
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let applied: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", version + 1)?;
+        transaction.commit()?;
+        tracing::info!("Applied database migration {}", version + 1);
+    }
+    Ok(())
+}
+
+/* Open the database and bring its schema up to date, called once at server start-up */
+pub fn init(config: &StorageConfig) -> Result<&'static Storage, String> {
+    if let Some(storage) = STORAGE.get() {
+        return Ok(storage);
+    }
+    let storage = Storage::open(config)?;
+    tracing::info!("Storing history in {}", config.path);
+    Ok(STORAGE.get_or_init(|| storage))
+}
+
+/* None until init() succeeded */
+pub fn storage() -> Option<&'static Storage> {
+    STORAGE.get()
+}
+
+#[cfg(test)]
+impl Storage {
+    pub fn for_test(retention_days: u64) -> Storage {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        Storage {
+            connection: Mutex::new(connection),
+            retention_days,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{data::UnconfirmedTxs, dropped::DroppedTransaction};
+
+    type Row = (u64, u64, Option<u64>, Option<String>, Option<u32>);
+
+    /* first_seen, last_seen, left_at, outcome and confirmed_height of a mempool transaction */
+    fn row(storage: &Storage, tx_id: &str) -> Option<Row> {
+        use rusqlite::OptionalExtension;
+        storage
+            .query(|connection| {
+                connection
+                    .query_row(
+                        "SELECT first_seen, last_seen, left_at, outcome, confirmed_height
+                         FROM mempool_transactions WHERE tx_id = ?1",
+                        [tx_id],
+                        |row| {
+                            Ok((
+                                row.get(0)?,
+                                row.get(1)?,
+                                row.get(2)?,
+                                row.get(3)?,
+                                row.get(4)?,
+                            ))
+                        },
+                    )
+                    .optional()
+            })
+            .unwrap()
+    }
+
+    fn count(storage: &Storage, table: &str) -> u32 {
+        storage
+            .query(|connection| {
+                connection.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+            })
+            .unwrap()
+    }
+
+    fn snapshot(tx_ids: &[&str], snapshot_time: u64) -> VectorUnconfirmedTxs {
+        VectorUnconfirmedTxs::for_test(
+            tx_ids
+                .iter()
+                .map(|tx_id| UnconfirmedTxs::for_test(tx_id, &[], 1_000_000, 200))
+                .collect(),
+            snapshot_time,
+        )
+    }
+
+    fn dropped(tx_id: &str, left_at: u64, outcome: TransactionOutcome) -> DroppedTransaction {
+        DroppedTransaction {
+            tx_id: tx_id.to_string(),
+            size: 200,
+            fee: 1_000_000,
+            fee_rate: 5_000.0,
+            last_seen: 0,
+            left_at,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn migrates_an_empty_database() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        /* Running again applies nothing */
+        migrate(&mut connection).unwrap();
+
+        let version: usize = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        let outcomes: u32 = connection
+            .query_row(
+                "SELECT COUNT(*) FROM mempool_transactions WHERE outcome IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(outcomes, 0);
+    }
+
+    #[test]
+    fn persists_polls() {
+        let storage = Storage::for_test(0);
+        let none = Departures::default();
+        storage
+            .persist_poll(Some(&snapshot(&["a", "b"], 1_000)), &[], &none, 1_000)
+            .unwrap();
+        storage
+            .persist_poll(Some(&snapshot(&["a"], 2_000)), &[], &none, 2_000)
+            .unwrap();
+
+        assert_eq!(count(&storage, "snapshots"), 2);
+        assert_eq!(row(&storage, "a"), Some((1_000, 2_000, None, None, None)));
+        /* Unclassified, so still open */
+        assert_eq!(row(&storage, "b"), Some((1_000, 1_000, None, None, None)));
+        let (transaction_count, total_fees): (u32, u64) = storage
+            .query(|connection| {
+                connection.query_row(
+                    "SELECT transaction_count, total_fees FROM snapshots WHERE snapshot_time = 1000",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+            })
+            .unwrap();
+        assert_eq!((transaction_count, total_fees), (2, 2_000_000));
+    }
+
+    #[test]
+    fn closes_only_classified_departures() {
+        let storage = Storage::for_test(0);
+        storage
+            .persist_poll(
+                Some(&snapshot(&["a", "b", "c", "d"], 1_000)),
+                &[],
+                &Departures::default(),
+                1_000,
+            )
+            .unwrap();
+
+        let departures = Departures {
+            confirmed: vec!["a".to_string()],
+            dropped: vec![
+                dropped("b", 2_000, TransactionOutcome::DoubleSpent),
+                dropped("c", 2_000, TransactionOutcome::Dropped),
+            ],
+        };
+        storage
+            .persist_poll(Some(&snapshot(&[], 2_000)), &[], &departures, 2_000)
+            .unwrap();
+
+        assert_eq!(row(&storage, "a").unwrap().3.as_deref(), Some("confirmed"));
+        assert_eq!(
+            row(&storage, "b").unwrap().3.as_deref(),
+            Some("double_spent")
+        );
+        assert_eq!(row(&storage, "c").unwrap().2, Some(2_000));
+        assert_eq!(row(&storage, "c").unwrap().3.as_deref(), Some("dropped"));
+        assert_eq!(row(&storage, "d"), Some((1_000, 1_000, None, None, None)));
+
+        /* A confirmed row is never reclassified */
+        let departures = Departures {
+            confirmed: vec![],
+            dropped: vec![dropped("a", 3_000, TransactionOutcome::Dropped)],
+        };
+        storage
+            .persist_poll(Some(&snapshot(&[], 3_000)), &[], &departures, 3_000)
+            .unwrap();
+        assert_eq!(row(&storage, "a").unwrap().2, Some(2_000));
+        assert_eq!(row(&storage, "a").unwrap().3.as_deref(), Some("confirmed"));
+    }
+
+    #[test]
+    fn prunes_rows_past_retention() {
+        let storage = Storage::for_test(1);
+        let old_block = ConfirmedBlock {
+            height: 10,
+            timestamp: 1_000,
+            transaction_ids: vec!["old".to_string()],
+            ..ConfirmedBlock::default()
+        };
+        storage
+            .persist_poll(
+                Some(&snapshot(&["old"], 1_000)),
+                &[old_block],
+                &Departures::default(),
+                1_000,
+            )
+            .unwrap();
+
+        let now = 1_000 + 2 * MILLIS_PER_DAY;
+        let new_block = ConfirmedBlock {
+            height: 11,
+            timestamp: now,
+            transaction_ids: vec!["new".to_string()],
+            ..ConfirmedBlock::default()
+        };
+        storage
+            .persist_poll(
+                Some(&snapshot(&["new"], now)),
+                &[new_block],
+                &Departures::default(),
+                now,
+            )
+            .unwrap();
+
+        assert_eq!(count(&storage, "snapshots"), 1);
+        assert_eq!(count(&storage, "blocks"), 1);
+        assert_eq!(count(&storage, "block_transactions"), 1);
+        assert!(row(&storage, "old").is_none());
+        assert!(row(&storage, "new").is_some());
+    }
+}
@@ -0,0 +1,270 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::data::{UnconfirmedTxs, VectorUnconfirmedTxs};
+
+/* Server-Sent Events endpoint pushing MempoolEvent updates */
+pub const MEMPOOL_STREAM_PATH: &str = "/api/mempool/stream";
+
+/* Number of new blocks kept by the poller and by the client hook */
+pub const MAX_RECENT_BLOCKS: usize = 10;
+
+/* A block added to the chain since the previous update */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmedBlock {
+    pub height: u32,
+    pub id: String,
+    pub timestamp: u64,
+    pub transaction_ids: Vec<String>,
+    /* Inputs of the block's transactions, only used by the server */
+    #[serde(skip)]
+    pub spent_box_ids: Vec<String>,
+}
+
+/* Changes between two mempool snapshots */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolDiff {
+    pub added: Vec<UnconfirmedTxs>,
+    /* Transactions that left the mempool without being included in one of new_blocks */
+    pub removed: Vec<String>,
+    /* Transactions that left the mempool because they were included in one of new_blocks */
+    pub confirmed: Vec<String>,
+    pub new_blocks: Vec<ConfirmedBlock>,
+    pub mempool_size: u32,
+    pub total_size: u64,
+    pub total_value: u64,
+    pub snapshot_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum MempoolEvent {
+    /* Full snapshot, sent when a client connects */
+    Snapshot(VectorUnconfirmedTxs),
+    Diff(MempoolDiff),
+}
+
+#[cfg(not(feature = "server"))]
+impl VectorUnconfirmedTxs {
+    pub fn apply_diff(&mut self, diff: &MempoolDiff) {
+        self.unconfirmed_txs.retain(|transaction| {
+            !diff.removed.contains(&transaction.id) && !diff.confirmed.contains(&transaction.id)
+        });
+        self.unconfirmed_txs.extend(diff.added.iter().cloned());
+        self.mempool_size = diff.mempool_size;
+        self.total_size = diff.total_size;
+        self.total_value = diff.total_value;
+        self.snapshot_time = diff.snapshot_time;
+    }
+}
+
+#[cfg(feature = "server")]
+impl MempoolDiff {
+    pub fn between(
+        previous: &VectorUnconfirmedTxs,
+        current: &VectorUnconfirmedTxs,
+        new_blocks: Vec<ConfirmedBlock>,
+    ) -> MempoolDiff {
+        use std::collections::HashSet;
+
+        let previous_ids: HashSet<&str> = previous
+            .unconfirmed_txs
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .collect();
+        let current_ids: HashSet<&str> = current
+            .unconfirmed_txs
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .collect();
+        let included_ids: HashSet<&str> = new_blocks
+            .iter()
+            .flat_map(|block| block.transaction_ids.iter())
+            .map(|tx_id| tx_id.as_str())
+            .collect();
+
+        let added = current
+            .unconfirmed_txs
+            .iter()
+            .filter(|transaction| !previous_ids.contains(transaction.id.as_str()))
+            .cloned()
+            .collect();
+        let (confirmed, removed): (Vec<String>, Vec<String>) = previous_ids
+            .difference(&current_ids)
+            .map(|tx_id| tx_id.to_string())
+            .partition(|tx_id| included_ids.contains(tx_id.as_str()));
+
+        MempoolDiff {
+            added,
+            removed,
+            confirmed,
+            new_blocks,
+            mempool_size: current.mempool_size,
+            total_size: current.total_size,
+            total_value: current.total_value,
+            snapshot_time: current.snapshot_time,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.confirmed.is_empty()
+            && self.new_blocks.is_empty()
+    }
+}
+
+/* Axum handler streaming the poller snapshots to one client: a full snapshot first, then diffs */
+#[cfg(feature = "server")]
+pub async fn mempool_stream() -> axum::response::sse::Sse<
+    impl futures::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+> {
+    use crate::data::poller::poller;
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use std::sync::Arc;
+
+    /* Last snapshot and block height sent to this client */
+    type Sent = Option<(Arc<VectorUnconfirmedTxs>, u32)>;
+
+    let receiver = poller().subscribe();
+    let stream = futures::stream::unfold(
+        (receiver, None as Sent),
+        |(mut receiver, sent)| async move {
+            loop {
+                let event = match &sent {
+                    None => {
+                        let state = receiver
+                            .wait_for(|state| state.snapshot.is_some())
+                            .await
+                            .ok()?;
+                        let snapshot = state.snapshot.clone()?;
+                        let height = state.best_height;
+                        drop(state);
+
+                        let event = MempoolEvent::Snapshot(snapshot.as_ref().clone());
+                        (event, Some((snapshot, height)))
+                    }
+                    Some((previous, previous_height)) => {
+                        receiver.changed().await.ok()?;
+                        let state = receiver.borrow_and_update().clone();
+                        let Some(snapshot) = state.snapshot else {
+                            continue;
+                        };
+                        let new_blocks = state
+                            .recent_blocks
+                            .into_iter()
+                            .filter(|block| block.height > *previous_height)
+                            .collect();
+
+                        let diff = MempoolDiff::between(previous, &snapshot, new_blocks);
+                        if diff.is_empty() && Arc::ptr_eq(previous, &snapshot) {
+                            continue;
+                        }
+                        (
+                            MempoolEvent::Diff(diff),
+                            Some((snapshot, state.best_height)),
+                        )
+                    }
+                };
+
+                let (event, sent) = event;
+                let event = Event::default().json_data(&event).ok()?;
+                return Some((Ok(event), (receiver, sent)));
+            }
+        },
+    );
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/* Live mempool state, kept up to date from the server's event stream */
+#[derive(Clone, Copy)]
+pub struct MempoolStream {
+    pub mempool: Signal<Option<VectorUnconfirmedTxs>>,
+    pub new_blocks: Signal<Vec<ConfirmedBlock>>,
+    /* Only changes when a newer snapshot arrives, not on every diff applied to the same one */
+    pub snapshot_time: Memo<Option<u64>>,
+}
+
+pub fn use_mempool_stream() -> MempoolStream {
+    let mempool: Signal<Option<VectorUnconfirmedTxs>> = use_signal(|| None);
+    let new_blocks = use_signal(Vec::new);
+    let snapshot_time = use_memo(move || mempool.read().as_ref().map(|data| data.snapshot_time));
+
+    use_future(move || async move {
+        /* The stream is only consumed in the browser */
+        #[cfg(not(feature = "server"))]
+        listen_mempool_stream(mempool, new_blocks).await;
+    });
+
+    MempoolStream {
+        mempool,
+        new_blocks,
+        snapshot_time,
+    }
+}
+
+/* A server function call rerun whenever the stream moves to a newer snapshot */
+pub fn use_snapshot_resource<T, F>(
+    mempool_stream: MempoolStream,
+    mut fetch: impl FnMut() -> F + 'static,
+) -> Resource<T>
+where
+    T: 'static,
+    F: std::future::Future<Output = T> + 'static,
+{
+    use_resource(move || {
+        let _snapshot_time = mempool_stream.snapshot_time.read();
+        fetch()
+    })
+}
+
+#[cfg(not(feature = "server"))]
+async fn listen_mempool_stream(
+    mut mempool: Signal<Option<VectorUnconfirmedTxs>>,
+    mut new_blocks: Signal<Vec<ConfirmedBlock>>,
+) {
+    use dioxus_logger::tracing;
+    use futures::StreamExt;
+    use gloo::{net::eventsource::futures::EventSource, timers::future::TimeoutFuture};
+
+    loop {
+        let mut event_source = match EventSource::new(MEMPOOL_STREAM_PATH) {
+            Ok(event_source) => event_source,
+            Err(err) => {
+                tracing::error!("Failed to open mempool stream: {:?}", err);
+                return;
+            }
+        };
+
+        match event_source.subscribe("message") {
+            Ok(mut subscription) => {
+                while let Some(Ok((_, message))) = subscription.next().await {
+                    let Some(data) = message.data().as_string() else {
+                        continue;
+                    };
+                    match serde_json::from_str::<MempoolEvent>(&data) {
+                        Ok(MempoolEvent::Snapshot(snapshot)) => mempool.set(Some(snapshot)),
+                        Ok(MempoolEvent::Diff(diff)) => {
+                            if let Some(mempool) = mempool.write().as_mut() {
+                                mempool.apply_diff(&diff);
+                            }
+                            if !diff.new_blocks.is_empty() {
+                                let mut new_blocks = new_blocks.write();
+                                new_blocks.extend(diff.new_blocks);
+                                let overflow = new_blocks.len().saturating_sub(MAX_RECENT_BLOCKS);
+                                new_blocks.drain(..overflow);
+                            }
+                        }
+                        Err(err) => tracing::warn!("Invalid mempool event: {}", err),
+                    }
+                }
+            }
+            Err(err) => tracing::error!("Failed to subscribe to mempool stream: {:?}", err),
+        }
+
+        /* Connection lost, the server sends a fresh snapshot on reconnect */
+        event_source.close();
+        TimeoutFuture::new(5000).await;
+    }
+}
@@ -0,0 +1,262 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::data::UnconfirmedTxs;
+
+#[cfg(feature = "server")]
+use crate::data::{chains::MempoolGraph, fees::api_fetch_max_block_size};
+#[cfg(feature = "server")]
+use std::collections::{BTreeSet, BinaryHeap, HashSet};
+
+/* The mempool transactions a miner would most likely put in the next block */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockTemplate {
+    /* In inclusion order, parents always before their children */
+    pub transactions: Vec<UnconfirmedTxs>,
+    pub total_size: u64,
+    pub total_fees: u64,
+    pub max_block_size: u64,
+    /* Mempool transactions that did not make it into the template */
+    pub left_out: u32,
+    pub snapshot_time: u64,
+}
+
+/* Max-heap entry by package fee rate, ties broken by the position in the snapshot */
+#[cfg(feature = "server")]
+struct Candidate {
+    fee_rate: f64,
+    index: usize,
+}
+
+#[cfg(feature = "server")]
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+#[cfg(feature = "server")]
+impl Eq for Candidate {}
+
+#[cfg(feature = "server")]
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "server")]
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.fee_rate
+            .total_cmp(&other.fee_rate)
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+/* The transaction with its ancestors not in the template yet, parents first */
+#[cfg(feature = "server")]
+fn pending_package(
+    graph: &MempoolGraph,
+    depths: &[u32],
+    included: &[bool],
+    index: usize,
+) -> Vec<usize> {
+    let mut package: Vec<usize> = graph
+        .ancestors(index)
+        .into_iter()
+        .filter(|&ancestor| !included[ancestor])
+        .chain([index])
+        .collect();
+    package.sort_by_key(|&member| (depths[member], member));
+    package
+}
+
+#[cfg(feature = "server")]
+fn package_fee_rate(transactions: &[UnconfirmedTxs], package: &[usize]) -> f64 {
+    let size: u64 = package
+        .iter()
+        .map(|&member| transactions[member].size as u64)
+        .sum();
+    let fee: u64 = package
+        .iter()
+        .map(|&member| transactions[member].fee())
+        .sum();
+    match size {
+        0 => 0.0,
+        size => fee as f64 / size as f64,
+    }
+}
+
+#[cfg(feature = "server")]
+impl BlockTemplate {
+    /* Greedy fill by package fee rate: a transaction is picked together with its ancestors not
+     * in the template yet, so a high fee child pulls in its low fee parent. Packages too large
+     * for the space left are skipped, and so is any transaction spending a box the template
+     * already spends, which keeps out the losing side of a double spend. */
+    pub fn from_transactions(
+        transactions: &[UnconfirmedTxs],
+        max_block_size: u64,
+        snapshot_time: u64,
+    ) -> BlockTemplate {
+        let graph = MempoolGraph::new(transactions);
+        let depths = graph.depths();
+        let mut included = vec![false; transactions.len()];
+        let mut conflicting = vec![false; transactions.len()];
+        let mut spent: HashSet<&str> = HashSet::new();
+
+        let mut candidates: BinaryHeap<Candidate> = (0..transactions.len())
+            .map(|index| Candidate {
+                fee_rate: package_fee_rate(
+                    transactions,
+                    &pending_package(&graph, &depths, &included, index),
+                ),
+                index,
+            })
+            .collect();
+
+        let mut template = BlockTemplate {
+            max_block_size,
+            snapshot_time,
+            ..Default::default()
+        };
+        while let Some(Candidate { fee_rate, index }) = candidates.pop() {
+            if included[index] || conflicting[index] {
+                continue;
+            }
+            let package = pending_package(&graph, &depths, &included, index);
+            /* Pushed before one of its ancestors was included, the updated entry is queued too */
+            if package_fee_rate(transactions, &package) != fee_rate {
+                continue;
+            }
+
+            let size: u64 = package
+                .iter()
+                .map(|&member| transactions[member].size as u64)
+                .sum();
+            if template.total_size + size > max_block_size {
+                continue;
+            }
+
+            let mut package_inputs: HashSet<&str> = HashSet::new();
+            let double_spend = package
+                .iter()
+                .flat_map(|&member| transactions[member].inputs.iter())
+                .any(|input| {
+                    spent.contains(input.box_id.as_str())
+                        || !package_inputs.insert(input.box_id.as_str())
+                });
+            if double_spend {
+                conflicting[index] = true;
+                continue;
+            }
+
+            let mut updated: BTreeSet<usize> = BTreeSet::new();
+            for &member in package.iter() {
+                let transaction = &transactions[member];
+                included[member] = true;
+                template.total_size += transaction.size as u64;
+                template.total_fees += transaction.fee();
+                template.transactions.push(transaction.clone());
+                updated.extend(graph.descendants(member));
+            }
+            spent.extend(package_inputs);
+
+            for descendant in updated {
+                if included[descendant] || conflicting[descendant] {
+                    continue;
+                }
+                candidates.push(Candidate {
+                    fee_rate: package_fee_rate(
+                        transactions,
+                        &pending_package(&graph, &depths, &included, descendant),
+                    ),
+                    index: descendant,
+                });
+            }
+        }
+        template.left_out = (transactions.len() - template.transactions.len()) as u32;
+        template
+    }
+}
+
+#[server(GetBlockTemplate)]
+pub async fn get_block_template() -> Result<BlockTemplate, ServerFnError> {
+    let data = crate::data::poller::latest_snapshot().await?;
+    let max_block_size = api_fetch_max_block_size().await?;
+    Ok(BlockTemplate::from_transactions(
+        &data.unconfirmed_txs,
+        max_block_size,
+        data.snapshot_time,
+    ))
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    fn tx_ids(template: &BlockTemplate) -> Vec<&str> {
+        template
+            .transactions
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn orders_by_fee_rate() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("low", &["a"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("high", &["b"], 5_000_000, 1000),
+            UnconfirmedTxs::for_test("mid", &["c"], 2_000_000, 1000),
+        ];
+        let template = BlockTemplate::from_transactions(&transactions, 10_000, 0);
+        assert_eq!(tx_ids(&template), ["high", "mid", "low"]);
+        assert_eq!(template.total_size, 3000);
+        assert_eq!(template.total_fees, 8_000_000);
+        assert_eq!(template.left_out, 0);
+    }
+
+    #[test]
+    fn child_pays_for_parent() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("other", &["a"], 3_000_000, 1000),
+            UnconfirmedTxs::for_test("parent", &["b"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("child", &["parent:0"], 9_000_000, 1000),
+        ];
+        /* The parent and child package pays 5000 nanoERG/byte, more than "other" */
+        let template = BlockTemplate::from_transactions(&transactions, 10_000, 0);
+        assert_eq!(tx_ids(&template), ["parent", "child", "other"]);
+
+        /* A child never goes in without its parent */
+        let template = BlockTemplate::from_transactions(&transactions, 1500, 0);
+        assert_eq!(tx_ids(&template), ["other"]);
+    }
+
+    #[test]
+    fn respects_the_size_limit() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("large", &["a"], 8_000_000, 2000),
+            UnconfirmedTxs::for_test("small", &["b"], 1_000_000, 500),
+            UnconfirmedTxs::for_test("medium", &["c"], 3_000_000, 1000),
+        ];
+        let template = BlockTemplate::from_transactions(&transactions, 2600, 0);
+        assert_eq!(tx_ids(&template), ["large", "small"]);
+        assert_eq!(template.total_size, 2500);
+        assert_eq!(template.left_out, 1);
+    }
+
+    #[test]
+    fn keeps_one_side_of_a_double_spend() {
+        let transactions = vec![
+            UnconfirmedTxs::for_test("loser", &["a"], 1_000_000, 1000),
+            UnconfirmedTxs::for_test("winner", &["a"], 2_000_000, 1000),
+            UnconfirmedTxs::for_test("loser_child", &["loser:0"], 9_000_000, 1000),
+            UnconfirmedTxs::for_test("unrelated", &["b"], 1_000_000, 1000),
+        ];
+        let template = BlockTemplate::from_transactions(&transactions, 10_000, 0);
+        /* The loser's child lifts its package above the winner, the winner is then left out */
+        assert_eq!(tx_ids(&template), ["loser", "loser_child", "unrelated"]);
+        assert_eq!(template.left_out, 1);
+    }
+}
@@ -0,0 +1,388 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::utils::format::MAX_DECIMALS;
+
+#[cfg(feature = "server")]
+use crate::{
+    data::{
+        config::node,
+        data::{
+            api_fetch_optional, is_node_id, Asset, Output, UnconfirmedTxs,
+            NODE_BLOCKCHAIN_BOX_BY_ID, NODE_BLOCKCHAIN_TOKEN_BY_ID,
+        },
+    },
+    utils::register::{decode_constant, Constant, SValue},
+};
+#[cfg(feature = "server")]
+use dioxus_logger::tracing;
+#[cfg(feature = "server")]
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "server")]
+const NODE_UNSPENT_BOXES_BY_TOKEN_ID: &str = "/blockchain/box/unspent/byTokenId/";
+
+/* Unspent boxes scanned for the holder distribution, fetched HOLDER_PAGE_SIZE at a time */
+#[cfg(feature = "server")]
+const HOLDER_BOXES_LIMIT: u32 = 1000;
+#[cfg(feature = "server")]
+const HOLDER_PAGE_SIZE: u32 = 100;
+
+/* Token metadata lookups in flight at once when annotating assets */
+#[cfg(feature = "server")]
+const TOKEN_INFO_CONCURRENCY: usize = 8;
+
+/* Cached token metadata is dropped wholesale past this many entries */
+#[cfg(feature = "server")]
+const TOKEN_INFO_CACHE_LIMIT: usize = 10_000;
+
+/* Tokens the node could not resolve are looked up again after this long */
+#[cfg(feature = "server")]
+const TOKEN_INFO_RETRY: Duration = Duration::from_secs(600);
+
+/* Name and decimals, enough to render an amount of the token */
+#[cfg(feature = "server")]
+#[derive(Debug, Default, Clone, PartialEq)]
+struct TokenInfo {
+    name: Option<String>,
+    decimals: u32,
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug, Clone)]
+enum CachedTokenInfo {
+    Found(TokenInfo),
+    /* Unknown to the node when looked up, e.g. minted in the mempool or no indexer */
+    Missing(Instant),
+}
+
+/* Token decimals as minted, anything outside 0..=MAX_DECIMALS is treated as 0 */
+pub fn checked_decimals(decimals: i64) -> u32 {
+    match u32::try_from(decimals) {
+        Ok(decimals) if decimals <= MAX_DECIMALS => decimals,
+        _ => 0,
+    }
+}
+
+/* Node supplied decimals, checked like the ones decoded from registers */
+pub fn deserialize_decimals<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let decimals = Value::deserialize(deserializer)?;
+    Ok(decimals.as_i64().map(checked_decimals).unwrap_or_default())
+}
+
+/* EIP-4 R6 decimals of an issuing box, an Int or UTF-8 digits in a Coll[Byte] */
+#[cfg(feature = "server")]
+fn register_decimals(issuing_box: &Output) -> u32 {
+    register(issuing_box, "R6")
+        .and_then(|constant| match constant.value {
+            SValue::Int(decimals) => Some(decimals as i64),
+            _ => constant.as_text()?.trim().parse().ok(),
+        })
+        .map(checked_decimals)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHolder {
+    pub address: String,
+    pub amount: u64,
+}
+
+/* A token with the EIP-4 metadata decoded from its issuing box */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenDetails {
+    pub token_id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub decimals: u32,
+    /* EIP-4 R7 asset type, e.g. "NFT - picture artwork" */
+    pub token_type: Option<String>,
+    pub emission_amount: u64,
+    /* The box holding the freshly minted tokens */
+    pub issuing_box_id: String,
+    pub minting_tx_id: String,
+    pub creation_height: u32,
+    /* Largest holders first, aggregated from at most HOLDER_BOXES_LIMIT unspent boxes */
+    pub holders: Vec<TokenHolder>,
+    pub holder_boxes_capped: bool,
+}
+
+/* EIP-4 R7 values, the first byte is the category and the second the kind */
+#[cfg(feature = "server")]
+fn token_type(bytes: &[u8]) -> String {
+    match bytes {
+        [0x01, 0x01] => "NFT - picture artwork".to_string(),
+        [0x01, 0x02] => "NFT - audio artwork".to_string(),
+        [0x01, 0x03] => "NFT - video artwork".to_string(),
+        [0x01, 0x04] => "NFT - artwork collection".to_string(),
+        [0x01, 0x05] => "NFT - file attachment".to_string(),
+        [0x02, 0x01] => "Membership token - threshold signature".to_string(),
+        bytes => format!(
+            "Unknown ({})",
+            bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        ),
+    }
+}
+
+#[cfg(feature = "server")]
+fn register(output: &Output, register: &str) -> Option<Constant> {
+    decode_constant(output.additional_registers.get(register)?).ok()
+}
+
+#[cfg(feature = "server")]
+impl TokenDetails {
+    /* Token lookups need the node's extra indexer */
+    pub async fn api_fetch(token_id: &str) -> Result<Option<TokenDetails>, ServerFnError> {
+        if !is_node_id(token_id) {
+            return Ok(None);
+        }
+        let Some(node_token) =
+            api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_TOKEN_BY_ID, token_id)).await?
+        else {
+            return Ok(None);
+        };
+        let issuing_box_id = node_token["boxId"].as_str().unwrap_or_default().to_string();
+
+        let issuing_box: Output = node()
+            .get(&format!("{}{}", NODE_BLOCKCHAIN_BOX_BY_ID, issuing_box_id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        /* R4 name, R5 description and R6 decimals are all UTF-8 encoded Coll[Byte] */
+        let name = register(&issuing_box, "R4").and_then(|constant| constant.as_text());
+        let description = register(&issuing_box, "R5").and_then(|constant| constant.as_text());
+        let decimals = register_decimals(&issuing_box);
+        let token_type = register(&issuing_box, "R7").and_then(|constant| match constant.value {
+            SValue::Bytes(bytes) => Some(token_type(&bytes)),
+            _ => None,
+        });
+        let emission_amount = issuing_box
+            .assets
+            .iter()
+            .find(|asset| asset.token_id == token_id)
+            .map(|asset| asset.amount)
+            .or_else(|| node_token["emissionAmount"].as_u64())
+            .unwrap_or_default();
+
+        let (holders, holder_boxes_capped) = api_fetch_holders(token_id).await?;
+
+        Ok(Some(TokenDetails {
+            token_id: token_id.to_string(),
+            name,
+            description,
+            decimals,
+            token_type,
+            emission_amount,
+            issuing_box_id,
+            minting_tx_id: issuing_box.tx_id,
+            creation_height: issuing_box.creation_height,
+            holders,
+            holder_boxes_capped,
+        }))
+    }
+}
+
+#[cfg(feature = "server")]
+async fn api_fetch_holders(token_id: &str) -> Result<(Vec<TokenHolder>, bool), ServerFnError> {
+    let mut amounts: HashMap<String, u64> = HashMap::new();
+    let mut offset = 0;
+
+    while offset < HOLDER_BOXES_LIMIT {
+        let boxes: Vec<Value> = node()
+            .get(&format!(
+                "{}{}?offset={}&limit={}",
+                NODE_UNSPENT_BOXES_BY_TOKEN_ID, token_id, offset, HOLDER_PAGE_SIZE
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        for node_box in boxes.iter() {
+            let address = node_box["address"].as_str().unwrap_or_default().to_string();
+            let amount: u64 = node_box["assets"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|asset| asset["tokenId"].as_str() == Some(token_id))
+                .map(|asset| asset["amount"].as_u64().unwrap_or_default())
+                .sum();
+            *amounts.entry(address).or_default() += amount;
+        }
+
+        offset += boxes.len() as u32;
+        if (boxes.len() as u32) < HOLDER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    let mut holders: Vec<TokenHolder> = amounts
+        .into_iter()
+        .map(|(address, amount)| TokenHolder { address, amount })
+        .collect();
+    holders.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.address.cmp(&b.address)));
+    Ok((holders, offset >= HOLDER_BOXES_LIMIT))
+}
+
+/* Token metadata never changes, so found tokens are cached for the lifetime of the server */
+#[cfg(feature = "server")]
+fn token_infos() -> &'static Mutex<HashMap<String, CachedTokenInfo>> {
+    static TOKEN_INFOS: OnceLock<Mutex<HashMap<String, CachedTokenInfo>>> = OnceLock::new();
+    TOKEN_INFOS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[cfg(feature = "server")]
+async fn api_fetch_token_info(token_id: &str) -> Result<Option<TokenInfo>, ServerFnError> {
+    let Some(node_token) =
+        api_fetch_optional(format!("{}{}", NODE_BLOCKCHAIN_TOKEN_BY_ID, token_id)).await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(TokenInfo {
+        name: node_token["name"]
+            .as_str()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string),
+        decimals: node_token["decimals"]
+            .as_i64()
+            .map(checked_decimals)
+            .unwrap_or_default(),
+    }))
+}
+
+/* Fill in the name and decimals of every output asset, best effort */
+#[cfg(feature = "server")]
+pub async fn annotate_assets(transactions: &mut [UnconfirmedTxs]) {
+    annotate(
+        transactions
+            .iter_mut()
+            .flat_map(|transaction| transaction.outputs.iter_mut())
+            .flat_map(|output| output.assets.iter_mut())
+            .collect(),
+    )
+    .await;
+}
+
+#[cfg(feature = "server")]
+pub async fn annotate_output_assets(outputs: &mut [Output]) {
+    annotate(
+        outputs
+            .iter_mut()
+            .flat_map(|output| output.assets.iter_mut())
+            .collect(),
+    )
+    .await;
+}
+
+#[cfg(feature = "server")]
+async fn annotate(mut assets: Vec<&mut Asset>) {
+    use futures::StreamExt;
+
+    let token_ids: HashSet<String> = assets.iter().map(|asset| asset.token_id.clone()).collect();
+    let missing: Vec<String> = {
+        let cache = token_infos().lock().unwrap();
+        token_ids
+            .into_iter()
+            .filter(|token_id| match cache.get(token_id) {
+                Some(CachedTokenInfo::Found(_)) => false,
+                Some(CachedTokenInfo::Missing(since)) => since.elapsed() >= TOKEN_INFO_RETRY,
+                None => true,
+            })
+            .collect()
+    };
+
+    let fetched: Vec<(String, CachedTokenInfo)> = futures::stream::iter(missing)
+        .map(|token_id| async move {
+            match api_fetch_token_info(&token_id).await {
+                Ok(Some(info)) => Some((token_id, CachedTokenInfo::Found(info))),
+                Ok(None) => Some((token_id, CachedTokenInfo::Missing(Instant::now()))),
+                Err(err) => {
+                    /* Not cached, retried on the next call */
+                    tracing::warn!("Failed to fetch token {}: {}", token_id, err);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(TOKEN_INFO_CONCURRENCY)
+        .filter_map(|fetched| async move { fetched })
+        .collect()
+        .await;
+
+    let mut cache = token_infos().lock().unwrap();
+    if cache.len() + fetched.len() > TOKEN_INFO_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.extend(fetched);
+
+    for asset in assets.iter_mut() {
+        if let Some(CachedTokenInfo::Found(info)) = cache.get(&asset.token_id) {
+            asset.name = info.name.clone();
+            asset.decimals = info.decimals;
+        }
+    }
+}
+
+#[server(GetTokenData)]
+pub async fn get_token_data(token_id: String) -> Result<Option<TokenDetails>, ServerFnError> {
+    TokenDetails::api_fetch(&token_id).await
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    fn issuing_box(r6: &str) -> Output {
+        Output {
+            additional_registers: [("R6".to_string(), r6.to_string())].into(),
+            ..Output::default()
+        }
+    }
+
+    #[test]
+    fn reads_register_decimals() {
+        /* Coll[Byte] "2" */
+        assert_eq!(register_decimals(&issuing_box("0e0132")), 2);
+        /* Int 8 */
+        assert_eq!(register_decimals(&issuing_box("0410")), 8);
+        assert_eq!(register_decimals(&Output::default()), 0);
+    }
+
+    #[test]
+    fn rejects_out_of_range_register_decimals() {
+        /* Coll[Byte] "2000000000" */
+        assert_eq!(
+            register_decimals(&issuing_box("0e0a32303030303030303030")),
+            0
+        );
+        /* Coll[Byte] "20" */
+        assert_eq!(register_decimals(&issuing_box("0e023230")), 0);
+        /* Int -1 */
+        assert_eq!(register_decimals(&issuing_box("0401")), 0);
+        assert_eq!(checked_decimals(MAX_DECIMALS as i64), MAX_DECIMALS);
+        assert_eq!(checked_decimals(i64::MAX), 0);
+    }
+
+    #[test]
+    fn checks_node_decimals() {
+        let decimals = |json: &str| -> u32 {
+            let mut deserializer = serde_json::Deserializer::from_str(json);
+            deserialize_decimals(&mut deserializer).unwrap()
+        };
+        assert_eq!(decimals("6"), 6);
+        assert_eq!(decimals("4294967296"), 0);
+        assert_eq!(decimals("-3"), 0);
+        assert_eq!(decimals("null"), 0);
+    }
+}
@@ -1,29 +1,74 @@
 #![allow(non_snake_case)]
 
+#[cfg(feature = "server")]
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 use dioxus::prelude::*;
-use dioxus_fullstack::Config;
 use dioxus_logger::tracing;
 
-use data::data::get_server_data;
+use routes::address::AddressView;
 use routes::blockvisualizer::BlockVisualizer;
+use routes::boxdetails::BoxView;
+use routes::history::MempoolHistory;
 use routes::home::HomePage;
+use routes::mempool::MempoolPage;
+use routes::nextblock::ProjectedBlock;
+use routes::search::SearchPage;
+use routes::token::TokenView;
+use routes::transaction::TransactionView;
 
 mod routes {
+    pub mod address;
     pub mod blockvisualizer;
+    pub mod boxdetails;
+    pub mod history;
     pub mod home;
+    pub mod mempool;
+    pub mod nextblock;
+    pub mod search;
+    pub mod token;
+    pub mod transaction;
 }
 mod utils {
+    #[cfg(feature = "server")]
+    pub mod address;
     pub mod chart;
+    pub mod format;
+    #[cfg(feature = "server")]
+    pub mod register;
 }
 mod data {
+    pub mod address;
+    #[cfg(feature = "server")]
+    pub mod api;
+    pub mod blocks;
+    pub mod chains;
+    #[cfg(feature = "server")]
+    pub mod config;
+    pub mod conflicts;
+    #[allow(clippy::module_inception)]
     pub mod data;
+    pub mod dropped;
+    pub mod fees;
+    pub mod history;
+    pub mod lifecycle;
+    #[cfg(feature = "server")]
+    pub mod poller;
+    pub mod search;
+    #[cfg(feature = "server")]
+    pub mod storage;
+    pub mod stream;
+    pub mod template;
+    pub mod token;
 }
 
 // Urls are relative to your Cargo.toml file
 const _TAILWIND_URL: &str = manganis::mg!(file("public/tailwind.css"));
 
+/* Pause in typing before the NavBar asks the server for search suggestions */
+#[cfg(not(feature = "server"))]
+const SEARCH_DEBOUNCE_MS: u32 = 250;
+
 #[derive(Clone, Routable, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 #[rustfmt::skip]
 enum Route {
@@ -32,6 +77,22 @@ enum Route {
         HomepageWrapper {},
         #[route("/block/:block_height")]
         BlockVisualizerWrapper { block_height: u32 },
+        #[route("/tx/:tx_id")]
+        TransactionWrapper { tx_id: String },
+        #[route("/box/:box_id")]
+        BoxWrapper { box_id: String },
+        #[route("/address/:address")]
+        AddressWrapper { address: String },
+        #[route("/token/:token_id")]
+        TokenWrapper { token_id: String },
+        #[route("/search/:query")]
+        SearchWrapper { query: String },
+        #[route("/mempool")]
+        MempoolWrapper {},
+        #[route("/mempool/next-block")]
+        ProjectedBlockWrapper {},
+        #[route("/mempool/history")]
+        MempoolHistoryWrapper {},
     #[end_layout]
     #[route("/:route")]
     PageNotFound { route: String },
@@ -61,16 +122,59 @@ fn HomepageWrapper() -> Element {
 
 #[component]
 fn BlockVisualizerWrapper(block_height: u32) -> Element {
-    rsx!({
-        BlockVisualizer(routes::blockvisualizer::BlockVisualizerProps {
-            block_height: block_height,
-        })
-    })
+    rsx!({ BlockVisualizer(routes::blockvisualizer::BlockVisualizerProps { block_height }) })
+}
+
+#[component]
+fn TransactionWrapper(tx_id: String) -> Element {
+    rsx!({ TransactionView(routes::transaction::TransactionViewProps { tx_id }) })
+}
+
+#[component]
+fn BoxWrapper(box_id: String) -> Element {
+    rsx!({ BoxView(routes::boxdetails::BoxViewProps { box_id }) })
+}
+
+#[component]
+fn AddressWrapper(address: String) -> Element {
+    rsx!({ AddressView(routes::address::AddressViewProps { address }) })
+}
+
+#[component]
+fn TokenWrapper(token_id: String) -> Element {
+    rsx!({ TokenView(routes::token::TokenViewProps { token_id }) })
+}
+
+#[component]
+fn SearchWrapper(query: String) -> Element {
+    rsx!({ SearchPage(routes::search::SearchPageProps { query }) })
+}
+
+#[component]
+fn MempoolWrapper() -> Element {
+    rsx!({ MempoolPage() })
+}
+
+#[component]
+fn ProjectedBlockWrapper() -> Element {
+    rsx!({ ProjectedBlock() })
+}
+
+#[component]
+fn MempoolHistoryWrapper() -> Element {
+    rsx!({ MempoolHistory() })
 }
 #[component]
 fn NavBar() -> Element {
-    let mut block_height = use_signal(|| 0);
+    let mut search_query = use_signal(String::new);
     let navigator = use_navigator();
+    let search_suggestions = use_resource(move || async move {
+        let query = search_query();
+        /* Typing again restarts the resource, dropping this call before it reaches the server */
+        #[cfg(not(feature = "server"))]
+        gloo::timers::future::TimeoutFuture::new(SEARCH_DEBOUNCE_MS).await;
+        data::search::get_search_suggestions(query).await
+    });
     let mut dropdown_menu_toggle = use_signal(|| false);
     let mut dropdown_menu_style = use_signal(|| {
         "visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"
@@ -106,7 +210,7 @@ fn NavBar() -> Element {
                             div {class:"grid grid-rows-5 justify-center items-center",
                                 div { Link {onclick: move |_| {dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);},to: Route::HomepageWrapper {}, class:"font-bold text-slate-200 rounded-lg hover:text-slate-100 m-2 ", "Home"}}
 
-                                div { Link {onclick: move |_| {/*dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);*/},to: Route::HomepageWrapper {}, class:"font-bold text-gray-500 rounded-lg hover:text-slate-100 m-2 ", "Mempool Visualizer"}}
+                                div { Link {onclick: move |_| {dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);},to: Route::MempoolWrapper {}, class:"font-bold text-slate-200 rounded-lg hover:text-slate-100 m-2 ", "Mempool Visualizer"}}
 
                                 div { Link {onclick: move |_| {/*dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);*/},to:"/", class:"font-bold text-gray-500 rounded-lg hover:text-slate-100 m-2 ", "Support"}}
 
@@ -115,7 +219,10 @@ fn NavBar() -> Element {
                                 div {
                                     form {
                                         onsubmit: move |_| {
-                                            navigator.push(Route::BlockVisualizerWrapper { block_height: block_height() });
+                                            let query = search_query().trim().to_string();
+                                            if !query.is_empty() {
+                                                navigator.push(Route::SearchWrapper { query });
+                                            }
                                             dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear");
                                             dropdown_menu_toggle.set(false);
                                         },
@@ -123,10 +230,12 @@ fn NavBar() -> Element {
                                             input {
                                                 r#type: "text",
                                                 class: "bg-white/30 border py-2 px-2 border-slate-300 placeholder-slate-100 focus:outline-none focus:border-slate-500 focus:ring-slate-300 block w-full rounded-full sm:text-sm focus:ring-1",
-                                                placeholder: "Enter block height",
-                                                name: "blockheight",
+                                                placeholder: "Search height, id or address",
+                                                name: "search",
+                                                list: "search_suggestions",
+                                                value: "{search_query}",
                                                 oninput: move |input| {
-                                                    block_height.set(input.value().parse::<u32>().unwrap());
+                                                    search_query.set(input.value());
                                                 },
                                             }
                                         }
@@ -139,7 +248,7 @@ fn NavBar() -> Element {
                     div {class:"hidden sm:block sm:grid sm:grid-cols-5 justify-items-center items-center text-center content-center sm:h-fit sm:w-full",
                         div { Link {onclick: move |_| {dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);},to: Route::HomepageWrapper {}, class:"font-bold text-slate-200 rounded-lg hover:text-slate-100 m-2 ", "Home"}}
 
-                        div { Link {onclick: move |_| {/*dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);*/},to: Route::HomepageWrapper {}, class:"font-bold text-gray-500 rounded-lg hover:text-slate-100 m-2 ", "Mempool Visualizer"}}
+                        div { Link {onclick: move |_| {dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);},to: Route::MempoolWrapper {}, class:"font-bold text-slate-200 rounded-lg hover:text-slate-100 m-2 ", "Mempool Visualizer"}}
 
                         div { Link {onclick: move |_| {/*dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear"); dropdown_menu_toggle.set(false);*/},to:"/", class:"font-bold text-gray-500 rounded-lg hover:text-slate-100 m-2 ", "Support"}}
 
@@ -148,7 +257,10 @@ fn NavBar() -> Element {
                         div {
                             form {
                                 onsubmit: move |_| {
-                                    navigator.push(Route::BlockVisualizerWrapper { block_height: block_height() });
+                                    let query = search_query().trim().to_string();
+                                    if !query.is_empty() {
+                                        navigator.push(Route::SearchWrapper { query });
+                                    }
                                     dropdown_menu_style.set("visibility: hidden; opacity: 0; transition: visibility 1s, opacity 0.2s linear");
                                     dropdown_menu_toggle.set(false);
                                 },
@@ -156,10 +268,12 @@ fn NavBar() -> Element {
                                     input {
                                         r#type: "text",
                                         class: "bg-white/30 border py-2 px-2 border-slate-300 placeholder-slate-100 focus:outline-none focus:border-slate-500 focus:ring-slate-300 block w-full rounded-full sm:text-sm focus:ring-1",
-                                        placeholder: "Enter block height",
-                                        name: "blockheight",
+                                        placeholder: "Search height, id or address",
+                                        name: "search",
+                                        list: "search_suggestions",
+                                        value: "{search_query}",
                                         oninput: move |input| {
-                                            block_height.set(input.value().parse::<u32>().unwrap());
+                                            search_query.set(input.value());
                                         },
                                     }
                                 }
@@ -178,6 +292,14 @@ fn NavBar() -> Element {
             br{}
         }
 
+        datalist {id:"search_suggestions",
+            if let Some(Ok(suggestions)) = &*search_suggestions.read_unchecked() {
+                for suggestion in suggestions.iter() {
+                    option {value:"{suggestion.query()}", "{suggestion.label()}"}
+                }
+            }
+        }
+
         Outlet::<Route> {}
     }
 }
@@ -200,18 +322,60 @@ fn main() {
     dioxus_logger::init(tracing::Level::INFO).expect("failed to init logger");
     tracing::info!("starting app");
 
-    let debug_flag = true;
+    #[cfg(feature = "server")]
+    {
+        let config = match data::config::ConfigFile::load() {
+            Ok(config) => config,
+            Err(err) => {
+                tracing::error!("{}", err);
+                std::process::exit(1);
+            }
+        };
+        if let Err(err) = data::config::init(config.node) {
+            tracing::error!("{}", err);
+            std::process::exit(1);
+        }
+        if let Err(err) = data::storage::init(&config.storage) {
+            tracing::error!("{}", err);
+            std::process::exit(1);
+        }
+
+        let debug_flag = true;
 
-    let mut serve_on_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 8070);
+        let mut serve_on_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 8070);
+
+        if debug_flag {
+            serve_on_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8070);
+        }
 
-    if debug_flag {
-        serve_on_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8070);
+        /* Serve the app on our own axum router so non server-function routes can be added */
+        tokio::runtime::Runtime::new()
+            .expect("failed to start tokio runtime")
+            .block_on(async move {
+                /* Start polling the node before the first client connects */
+                data::poller::poller();
+
+                let router = axum::Router::new()
+                    .route(
+                        data::stream::MEMPOOL_STREAM_PATH,
+                        axum::routing::get(data::stream::mempool_stream),
+                    )
+                    .nest(data::api::API_PREFIX, data::api::router())
+                    .serve_dioxus_application(ServeConfig::builder(), || VirtualDom::new(App))
+                    .await;
+
+                tracing::info!("Listening on {}", serve_on_addr);
+                let listener = tokio::net::TcpListener::bind(serve_on_addr)
+                    .await
+                    .expect("failed to bind server address");
+                axum::serve(listener, router.into_make_service())
+                    .await
+                    .expect("server error");
+            });
     }
 
-    // launch_fullstack(app);
-    LaunchBuilder::new()
-        .with_cfg(server_only! {Config::new().addr(serve_on_addr)})
-        .launch(App);
+    #[cfg(not(feature = "server"))]
+    LaunchBuilder::new().launch(App);
 }
 
 #[server(PostServerData)]
@@ -0,0 +1,144 @@
+use crate::{
+    data::address::{
+        get_address_data, get_address_history, AddressDetails, Balance, HISTORY_PAGE_SIZE,
+        UNSPENT_BOXES_LIMIT,
+    },
+    utils::{
+        chart::BalanceChart,
+        format::{format_amount, format_erg, format_timestamp},
+    },
+    Route,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn AddressView(address: String) -> Element {
+    let address_data = use_resource(use_reactive((&address,), |(address,)| async move {
+        get_address_data(address).await
+    }));
+
+    match &*address_data.read_unchecked() {
+        Some(Ok(Some(details))) => rsx!(
+            AddressDetailsView { details: details.clone() }
+            AddressHistoryView { address: address.clone() }
+        ),
+        Some(Ok(None)) => rsx!(
+            h1{class:"text-slate-200 break-all", "{address} is not a valid Ergo address"}
+        ),
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
+
+#[component]
+fn AddressDetailsView(details: AddressDetails) -> Element {
+    rsx!(
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold break-all", "ADDRESS: {details.address}"}
+            h1{class:"text-slate-200", "CONFIRMED_BALANCE: {format_erg(details.confirmed.nano_ergs)}"}
+            h1{class:"text-slate-200", "UNCONFIRMED_BALANCE: {format_erg(details.unconfirmed.nano_ergs)}"}
+        }
+
+        if !details.balance_history.is_empty() {
+            BalanceChart { chart_id: "balance_chart_{details.address}", points: details.balance_history.clone() }
+        }
+
+        TokenBalances { label: "CONFIRMED_TOKENS", balance: details.confirmed.clone() }
+        TokenBalances { label: "UNCONFIRMED_TOKENS", balance: details.unconfirmed.clone() }
+
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold", "UNSPENT_BOXES: {details.unspent_boxes.len()}"}
+            if details.unspent_boxes.len() as u32 == UNSPENT_BOXES_LIMIT {
+                h1{class:"text-slate-200 mb-4", "Showing the newest {UNSPENT_BOXES_LIMIT} boxes"}
+            }
+            for output in details.unspent_boxes.iter() {
+                div {class:"mb-4",
+                    h1{class:"text-slate-200 break-all",
+                        "BOX_ID: "
+                        Link {class:"underline", to: Route::BoxWrapper { box_id: output.box_id.clone() }, "{output.box_id}"}
+                    }
+                    h1{class:"text-slate-200", "VALUE: {format_erg(output.value)}"}
+                    h1{class:"text-slate-200", "ASSETS: {output.assets.len()}"}
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn TokenBalances(label: String, balance: Balance) -> Element {
+    if balance.tokens.is_empty() {
+        return rsx!();
+    }
+
+    rsx!(
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold", "{label}: {balance.tokens.len()}"}
+            for token in balance.tokens.iter() {
+                h1{class:"text-slate-200 break-all",
+                    Link {class:"underline", to: Route::TokenWrapper { token_id: token.token_id.clone() },
+                        "{token.name.clone().unwrap_or_else(|| token.token_id.clone())}"
+                    }
+                    ": {format_amount(token.amount, token.decimals)}"
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn AddressHistoryView(address: String) -> Element {
+    let mut page = use_signal(|| 0u32);
+    let address_history = use_resource(use_reactive((&address,), move |(address,)| async move {
+        get_address_history(address, page()).await
+    }));
+
+    match &*address_history.read_unchecked() {
+        Some(Ok(Some(history))) => {
+            let current_page = page();
+            let page_count = history.total.div_ceil(HISTORY_PAGE_SIZE).max(1);
+
+            rsx!(
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    h1{class:"text-slate-200 font-bold", "TRANSACTIONS: {history.total}"}
+                    for entry in history.transactions.iter() {
+                        div {class:"mb-4",
+                            h1{class:"text-slate-200 break-all",
+                                "ID: "
+                                Link {class:"underline", to: Route::TransactionWrapper { tx_id: entry.transaction.id.clone() }, "{entry.transaction.id}"}
+                            }
+                            h1{class:"text-slate-200",
+                                "HEIGHT: "
+                                Link {class:"underline", to: Route::BlockVisualizerWrapper { block_height: entry.inclusion_height }, "{entry.inclusion_height}"}
+                            }
+                            h1{class:"text-slate-200", "TIMESTAMP: {format_timestamp(entry.timestamp)}"}
+                            h1{class:"text-slate-200", "BALANCE_CHANGE: {format_balance_change(entry.balance_change)}"}
+                        }
+                    }
+
+                    div {class:"flex justify-center items-center space-x-4 mt-6",
+                        button {class:"text-slate-200 rounded-full bg-white/30 px-4 py-1 disabled:text-gray-500",
+                            disabled: current_page == 0,
+                            onclick: move |_| page.set(current_page.saturating_sub(1)),
+                            "Previous"
+                        }
+                        h1{class:"text-slate-200", "PAGE: {current_page + 1} / {page_count}"}
+                        button {class:"text-slate-200 rounded-full bg-white/30 px-4 py-1 disabled:text-gray-500",
+                            disabled: current_page + 1 >= page_count,
+                            onclick: move |_| page.set(current_page + 1),
+                            "Next"
+                        }
+                    }
+                }
+            )
+        }
+        Some(Ok(None)) => rsx!(),
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
+
+fn format_balance_change(nano_ergs: i64) -> String {
+    let sign = if nano_ergs < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_erg(nano_ergs.unsigned_abs()))
+}
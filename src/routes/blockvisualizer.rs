@@ -1,21 +1,79 @@
-use crate::{data::data::get_server_data, utils::chart::Chart};
+use crate::{
+    data::data::{get_block_data, Block},
+    routes::token::AssetLink,
+    utils::{
+        chart::Chart,
+        format::{format_erg, format_timestamp},
+    },
+    Route,
+};
 use dioxus::prelude::*;
-use gloo::timers::future::TimeoutFuture;
 
 #[component]
 pub fn BlockVisualizer(block_height: u32) -> Element {
-    let mut server_data = use_resource(move || async move { get_server_data().await });
+    let block_data = use_resource(use_reactive(
+        (&block_height,),
+        |(block_height,)| async move { get_block_data(block_height).await },
+    ));
 
-    use_future(move || async move {
-        loop {
-            TimeoutFuture::new(5000).await;
-            server_data.restart()
-        }
-    });
-
-    match &*server_data.read_unchecked() {
-        Some(Ok(data)) => rsx!("{data:?}"),
+    match &*block_data.read_unchecked() {
+        Some(Ok(blocks)) if blocks.is_empty() => rsx!(
+            h1{class:"text-slate-200", "No block found at height {block_height}"}
+        ),
+        Some(Ok(blocks)) => rsx!(for block in blocks.iter() {
+            BlockView {
+                block: block.clone(),
+            }
+        }),
         Some(Err(err)) => rsx!("{err:?}"),
         None => rsx!(),
     }
 }
+
+#[component]
+fn BlockView(block: Block) -> Element {
+    let header = &block.header;
+
+    rsx!(
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold", "BLOCK: {header.height}"}
+            h1{class:"text-slate-200 break-all", "ID: {header.id}"}
+            h1{class:"text-slate-200 break-all", "PARENT_ID: {header.parent_id}"}
+            h1{class:"text-slate-200", "TIMESTAMP: {format_timestamp(header.timestamp)}"}
+            h1{class:"text-slate-200", "VERSION: {header.version}"}
+            h1{class:"text-slate-200", "DIFFICULTY: {header.difficulty}"}
+            h1{class:"text-slate-200", "N_BITS: {header.n_bits}"}
+            h1{class:"text-slate-200", "VOTES: {header.votes}"}
+            h1{class:"text-slate-200 break-all", "MINER_PK: {header.miner_pk}"}
+            h1{class:"text-slate-200 break-all", "STATE_ROOT: {header.state_root}"}
+            h1{class:"text-slate-200 break-all", "AD_PROOFS_ROOT: {header.ad_proofs_root}"}
+            h1{class:"text-slate-200 break-all", "TRANSACTIONS_ROOT: {header.transactions_root}"}
+            h1{class:"text-slate-200 break-all", "EXTENSION_HASH: {header.extension_hash}"}
+            h1{class:"text-slate-200", "EXTENSION_FIELDS: {block.extension.fields.len()}"}
+            h1{class:"text-slate-200", "AD_PROOFS_SIZE: {block.ad_proofs_size}"}
+            h1{class:"text-slate-200", "SIZE: {block.size}"}
+            h1{class:"text-slate-200", "TRANSACTIONS: {block.transactions.len()}"}
+        }
+
+        Chart { chart_id: "block_chart_{header.id}", transactions: block.transactions.clone() }
+
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            for transaction in block.transactions.iter() {
+                div {class:"mb-4",
+                    h1{class:"text-slate-200 break-all",
+                        "ID: "
+                        Link {class:"underline", to: Route::TransactionWrapper { tx_id: transaction.id.clone() }, "{transaction.id}"}
+                    }
+                    h1{class:"text-slate-200", "INPUTS: {transaction.inputs.len()}"}
+                    h1{class:"text-slate-200", "DATA_INPUTS: {transaction.data_inputs.len()}"}
+                    h1{class:"text-slate-200", "OUTPUTS: {transaction.outputs.len()}"}
+                    h1{class:"text-slate-200", "SIZE: {transaction.size}"}
+                    h1{class:"text-slate-200", "VALUE: {format_erg(transaction.output_value())}"}
+                    for asset in transaction.output_assets() {
+                        AssetLink { asset }
+                    }
+                }
+            }
+        }
+    )
+}
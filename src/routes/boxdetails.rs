@@ -0,0 +1,103 @@
+use crate::{
+    data::data::{get_box_data, BoxDetails, SpendingStatus},
+    routes::token::AssetLink,
+    utils::format::format_erg,
+    Route,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn BoxView(box_id: String) -> Element {
+    let box_data = use_resource(use_reactive((&box_id,), |(box_id,)| async move {
+        get_box_data(box_id).await
+    }));
+
+    match &*box_data.read_unchecked() {
+        Some(Ok(Some(details))) => rsx!(BoxDetailsView {
+            details: details.clone()
+        }),
+        Some(Ok(None)) => rsx!(
+            h1{class:"text-slate-200 break-all", "No box found with id {box_id}"}
+        ),
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
+
+#[component]
+fn BoxDetailsView(details: BoxDetails) -> Element {
+    let output = &details.output;
+
+    rsx!(
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold break-all", "BOX: {output.box_id}"}
+            match &details.status {
+                SpendingStatus::Unspent => rsx!(h1{class:"text-slate-200", "STATUS: Unspent"}),
+                SpendingStatus::SpentInMempool { tx_id } => rsx!(
+                    h1{class:"text-slate-200", "STATUS: Spent in mempool"}
+                    h1{class:"text-slate-200 break-all",
+                        "SPENDING_TX: "
+                        Link {class:"underline", to: Route::TransactionWrapper { tx_id: tx_id.clone() }, "{tx_id}"}
+                    }
+                ),
+                SpendingStatus::SpentInBlock { tx_id } => rsx!(
+                    h1{class:"text-slate-200", "STATUS: Spent in block"}
+                    h1{class:"text-slate-200 break-all",
+                        "SPENDING_TX: "
+                        Link {class:"underline", to: Route::TransactionWrapper { tx_id: tx_id.clone() }, "{tx_id}"}
+                    }
+                ),
+            }
+            h1{class:"text-slate-200 break-all",
+                "CREATING_TX: "
+                Link {class:"underline", to: Route::TransactionWrapper { tx_id: output.tx_id.clone() }, "{output.tx_id}"}
+            }
+            h1{class:"text-slate-200", "INDEX: {output.index}"}
+            if let Some(height) = details.inclusion_height {
+                h1{class:"text-slate-200",
+                    "INCLUSION_HEIGHT: "
+                    Link {class:"underline", to: Route::BlockVisualizerWrapper { block_height: height }, "{height}"}
+                }
+            }
+            h1{class:"text-slate-200", "CREATION_HEIGHT: {output.creation_height}"}
+            h1{class:"text-slate-200", "VALUE: {format_erg(output.value)}"}
+            if let Some(address) = &details.address {
+                h1{class:"text-slate-200 break-all",
+                    "ADDRESS: "
+                    Link {class:"underline", to: Route::AddressWrapper { address: address.clone() }, "{address}"}
+                }
+            }
+            h1{class:"text-slate-200 break-all", "ERGO_TREE: {output.ergo_tree}"}
+        }
+
+        if !output.assets.is_empty() {
+            div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                h1{class:"text-slate-200 font-bold", "ASSETS: {output.assets.len()}"}
+                for asset in output.assets.iter() {
+                    AssetLink { asset: asset.clone() }
+                }
+            }
+        }
+
+        if !details.registers.is_empty() {
+            div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                h1{class:"text-slate-200 font-bold", "REGISTERS: {details.registers.len()}"}
+                for register in details.registers.iter() {
+                    div {class:"mb-4",
+                        match (&register.sigma_type, &register.value) {
+                            (Some(sigma_type), Some(value)) => rsx!(
+                                h1{class:"text-slate-200 break-all", "{register.register}: {sigma_type} = {value}"}
+                            ),
+                            _ => rsx!(
+                                h1{class:"text-slate-200 break-all", "{register.register}: {register.serialized_value}"}
+                            ),
+                        }
+                        if let Some(text) = &register.text {
+                            h1{class:"text-slate-200 break-all", "TEXT: {text}"}
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
@@ -0,0 +1,87 @@
+use crate::{
+    data::{
+        history::{get_mempool_history, HistoryPoint, HistoryWindow},
+        lifecycle::get_confirmation_times,
+    },
+    utils::{
+        chart::TimeSeriesChart,
+        format::{format_duration, ERG_DECIMALS},
+    },
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn MempoolHistory() -> Element {
+    let mut window = use_signal(HistoryWindow::default);
+    let history = use_resource(move || async move { get_mempool_history(window()).await });
+    let confirmation_times = use_resource(|| async move { get_confirmation_times().await });
+
+    rsx!(
+        div {class:"flex justify-center items-center space-x-4 mt-6",
+            for option in HistoryWindow::ALL {
+                button {class: if option == window() { "text-slate-200 rounded-full bg-white/50 px-4 py-1 font-bold" } else { "text-slate-200 rounded-full bg-white/30 px-4 py-1" },
+                    onclick: move |_| window.set(option),
+                    "{option.label()}"
+                }
+            }
+        }
+
+        match &*history.read_unchecked() {
+            Some(Ok(points)) if points.is_empty() => rsx!(
+                h1{class:"text-slate-200 mt-6", "No mempool history stored for the last {window().label()}"}
+            ),
+            Some(Ok(points)) => rsx!(HistoryCharts {
+                window: window(),
+                points: points.clone()
+            }),
+            Some(Err(err)) => rsx!("{err:?}"),
+            None => rsx!(),
+        }
+
+        if let Some(Ok(confirmation_times)) = &*confirmation_times.read_unchecked() {
+            div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                h1{class:"text-slate-200 font-bold", "TIME_TO_CONFIRMATION (nanoERG/byte)"}
+                for times in confirmation_times.iter().filter(|times| times.bucket.transaction_count > 0) {
+                    h1{class:"text-slate-200",
+                        "{times.bucket.label()}: MEDIAN {format_duration(times.median_time)}, AVERAGE {format_duration(times.average_time)} over {times.bucket.transaction_count} transactions"
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn HistoryCharts(window: HistoryWindow, points: Vec<HistoryPoint>) -> Element {
+    let timestamps: Vec<u64> = points.iter().map(|point| point.timestamp).collect();
+    let series =
+        |value: fn(&HistoryPoint) -> f64| -> Vec<f64> { points.iter().map(value).collect() };
+    let label = window.label();
+
+    rsx!(
+        TimeSeriesChart {
+            chart_id: "history_transactions_{label}",
+            name: "Transactions",
+            timestamps: timestamps.clone(),
+            values: series(|point| point.transaction_count)
+        }
+        TimeSeriesChart {
+            chart_id: "history_size_{label}",
+            name: "Total size (bytes)",
+            timestamps: timestamps.clone(),
+            values: series(|point| point.total_size)
+        }
+        TimeSeriesChart {
+            chart_id: "history_value_{label}",
+            name: "Total value (ERG)",
+            timestamps: timestamps.clone(),
+            values: series(|point| point.total_value / 10f64.powi(ERG_DECIMALS as i32))
+        }
+        TimeSeriesChart {
+            chart_id: "history_fee_rate_{label}",
+            name: "Median fee rate (nanoERG/byte)",
+            timestamps: timestamps.clone(),
+            values: series(|point| point.median_fee_rate)
+        }
+    )
+}
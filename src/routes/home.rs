@@ -1,30 +1,117 @@
-use crate::{data::data::get_server_data, utils::chart::Chart};
+use crate::{
+    data::{
+        blocks::{get_recent_blocks, BlockSummary},
+        stream::use_mempool_stream,
+    },
+    routes::token::AssetLink,
+    utils::{
+        chart::Chart,
+        format::{format_age, format_erg, format_timestamp},
+    },
+    Route,
+};
 use dioxus::prelude::*;
-use gloo::timers::future::TimeoutFuture;
+
+/* Number of mempool transactions listed (and charted) per page */
+const TXS_PER_PAGE: usize = 20;
 
 #[component]
 pub fn HomePage() -> Element {
-    let mut server_data = use_resource(move || async move { get_server_data().await });
-
-    use_future(move || async move {
-        loop {
-            TimeoutFuture::new(5000).await;
-            server_data.restart()
-        }
+    let mempool_stream = use_mempool_stream();
+    let mut page = use_signal(|| 0usize);
+    /* Refetched whenever the stream reports a new block */
+    let recent_blocks = use_resource(move || {
+        let _latest_height = mempool_stream
+            .new_blocks
+            .read()
+            .last()
+            .map(|block| block.height);
+        async move { get_recent_blocks().await }
     });
 
-    match &*server_data.read_unchecked() {
-        Some(Ok(data)) => rsx!(
-            for data_entry in data.unconfirmed_txs.iter() {
-                h1{class:"text-slate-200", "ID: {data_entry.id}"}
-                h1{class:"text-slate-200", "INPUTS: {data_entry.inputs.len()}"}
-                h1{class:"text-slate-200", "DATA_INPUTS: {data_entry.data_inputs.len()}"}
-                h1{class:"text-slate-200", "OUTPUTS: {data_entry.outputs.len()}"}
-                h1{class:"text-slate-200", "SIZE: {data_entry.size}"}
-            }
+    match &*mempool_stream.mempool.read_unchecked() {
+        Some(data) => {
+            let page_count = data.unconfirmed_txs.len().div_ceil(TXS_PER_PAGE).max(1);
+            /* The mempool can shrink between polls, keep the current page in range */
+            let current_page = page().min(page_count - 1);
+            let page_txs: Vec<_> = data
+                .unconfirmed_txs
+                .iter()
+                .skip(current_page * TXS_PER_PAGE)
+                .take(TXS_PER_PAGE)
+                .cloned()
+                .collect();
+
+            rsx!(
+                if let Some(Ok(blocks)) = &*recent_blocks.read_unchecked() {
+                    RecentBlocks { blocks: blocks.clone(), now: data.snapshot_time }
+                }
+
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    h1{class:"text-slate-200", "MEMPOOL_SIZE: {data.mempool_size}"}
+                    if data.mempool_size as usize > data.unconfirmed_txs.len() {
+                        h1{class:"text-slate-200", "FETCHED: {data.unconfirmed_txs.len()}"}
+                    }
+                    h1{class:"text-slate-200", "TOTAL_SIZE: {data.total_size} bytes"}
+                    h1{class:"text-slate-200", "TOTAL_VALUE: {format_erg(data.total_value)}"}
+                    h1{class:"text-slate-200", "SNAPSHOT_TIME: {format_timestamp(data.snapshot_time)}"}
+                    if let Some(block) = mempool_stream.new_blocks.read().last() {
+                        h1{class:"text-slate-200", "LATEST_BLOCK: {block.height} ({block.transaction_ids.len()} transactions)"}
+                    }
+                }
+
+                Chart { chart_id: "mempool_chart", transactions: page_txs.clone() }
 
-        ),
-        Some(Err(err)) => rsx!("{err:?}"),
+                for data_entry in page_txs.iter() {
+                    h1{class:"text-slate-200",
+                        "ID: "
+                        Link {class:"underline", to: Route::TransactionWrapper { tx_id: data_entry.id.clone() }, "{data_entry.id}"}
+                    }
+                    h1{class:"text-slate-200", "INPUTS: {data_entry.inputs.len()}"}
+                    h1{class:"text-slate-200", "DATA_INPUTS: {data_entry.data_inputs.len()}"}
+                    h1{class:"text-slate-200", "OUTPUTS: {data_entry.outputs.len()}"}
+                    h1{class:"text-slate-200", "SIZE: {data_entry.size}"}
+                    h1{class:"text-slate-200", "VALUE: {format_erg(data_entry.output_value())}"}
+                    for asset in data_entry.output_assets() {
+                        AssetLink { asset }
+                    }
+                }
+
+                div {class:"flex justify-center items-center space-x-4 mt-6",
+                    button {class:"text-slate-200 rounded-full bg-white/30 px-4 py-1 disabled:text-gray-500",
+                        disabled: current_page == 0,
+                        onclick: move |_| page.set(current_page.saturating_sub(1)),
+                        "Previous"
+                    }
+                    h1{class:"text-slate-200", "PAGE: {current_page + 1} / {page_count}"}
+                    button {class:"text-slate-200 rounded-full bg-white/30 px-4 py-1 disabled:text-gray-500",
+                        disabled: current_page + 1 >= page_count,
+                        onclick: move |_| page.set(current_page + 1),
+                        "Next"
+                    }
+                }
+            )
+        }
         None => rsx!(),
     }
 }
+
+/* Newest block first, ages relative to `now` in unix milliseconds */
+#[component]
+fn RecentBlocks(blocks: Vec<BlockSummary>, now: u64) -> Element {
+    rsx!(
+        div {class:"flex space-x-4 overflow-x-auto mt-6 pb-2",
+            for block in blocks.iter() {
+                Link {class:"shrink-0 w-48 bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 hover:bg-opacity-20",
+                    to: Route::BlockVisualizerWrapper { block_height: block.height },
+                    h1{class:"text-slate-200 font-bold", "{block.height}"}
+                    h1{class:"text-slate-200", "{format_age(block.timestamp, now)}"}
+                    h1{class:"text-slate-200", "TXS: {block.transaction_count}"}
+                    h1{class:"text-slate-200", "SIZE: {block.size} bytes"}
+                    h1{class:"text-slate-200", "REWARD: {format_erg(block.miner_reward)}"}
+                    h1{class:"text-slate-200", "FEES: {format_erg(block.total_fees)}"}
+                }
+            }
+        }
+    )
+}
@@ -0,0 +1,161 @@
+use crate::{
+    data::{
+        chains::{get_mempool_chains, TransactionChain},
+        conflicts::get_double_spends,
+        dropped::{get_dropped_transactions, DroppedTransaction},
+        fees::{fee_rate_histogram, get_fee_estimate, get_fee_stats, FeeRecommendation},
+        lifecycle::TransactionOutcome,
+        stream::{use_mempool_stream, use_snapshot_resource},
+    },
+    utils::{
+        chart::{ChainGraph, FeeRateHistogram, MempoolChart},
+        format::{format_erg, format_fee_rate, format_timestamp},
+    },
+    Route,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn MempoolPage() -> Element {
+    let mempool_stream = use_mempool_stream();
+    let fee_stats = use_snapshot_resource(mempool_stream, get_fee_stats);
+    /* 0 asks for the server's typical transaction size */
+    let fee_estimate = use_snapshot_resource(mempool_stream, || get_fee_estimate(0));
+    let double_spends = use_snapshot_resource(mempool_stream, get_double_spends);
+    let dropped = use_snapshot_resource(mempool_stream, get_dropped_transactions);
+    let mempool_chains = use_snapshot_resource(mempool_stream, get_mempool_chains);
+
+    match &*mempool_stream.mempool.read_unchecked() {
+        Some(data) => rsx!(
+            div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                h1{class:"text-slate-200", "MEMPOOL_SIZE: {data.mempool_size}"}
+                if data.mempool_size as usize > data.unconfirmed_txs.len() {
+                    h1{class:"text-slate-200", "FETCHED: {data.unconfirmed_txs.len()}"}
+                }
+                h1{class:"text-slate-200", "TOTAL_SIZE: {data.total_size} bytes"}
+                h1{class:"text-slate-200", "SNAPSHOT_TIME: {format_timestamp(data.snapshot_time)}"}
+                h1{class:"text-slate-200",
+                    Link {class:"underline", to: Route::ProjectedBlockWrapper {}, "Projected next block"}
+                }
+                h1{class:"text-slate-200",
+                    Link {class:"underline", to: Route::MempoolHistoryWrapper {}, "Mempool history"}
+                }
+                h1{class:"text-slate-200 mt-4", "Bubble area is the transaction size, color its fee rate. Click a bubble to open the transaction."}
+            }
+
+            if let Some(Ok(double_spends)) = &*double_spends.read_unchecked() {
+                if double_spends.iter().any(|double_spend| double_spend.active) {
+                    div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                        for double_spend in double_spends.iter().filter(|double_spend| double_spend.active) {
+                            div {class:"mb-4",
+                                DoubleSpendBadge {}
+                                h1{class:"text-slate-200 break-all",
+                                    "BOX: "
+                                    Link {class:"underline", to: Route::BoxWrapper { box_id: double_spend.box_id.clone() }, "{double_spend.box_id}"}
+                                }
+                                h1{class:"text-slate-200", "FIRST_SEEN: {format_timestamp(double_spend.first_seen)}"}
+                                for tx_id in double_spend.tx_ids.iter() {
+                                    h1{class:"text-slate-200 break-all",
+                                        "SPENT_BY: "
+                                        Link {class:"underline", to: Route::TransactionWrapper { tx_id: tx_id.clone() }, "{tx_id}"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(Ok(dropped)) = &*dropped.read_unchecked() {
+                if !dropped.is_empty() {
+                    div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                        h1{class:"text-slate-200 font-bold", "RECENTLY_DROPPED: {dropped.len()}"}
+                        for transaction in dropped.iter().take(MAX_DROPPED_SHOWN) {
+                            DroppedTransactionView { transaction: transaction.clone() }
+                        }
+                    }
+                }
+            }
+
+            if let Some(Ok(stats)) = &*fee_stats.read_unchecked() {
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    h1{class:"text-slate-200", "TOTAL_FEES: {format_erg(stats.total_fees)}"}
+                    h1{class:"text-slate-200", "MIN_FEE_RATE: {format_fee_rate(stats.min_fee_rate)}"}
+                    h1{class:"text-slate-200", "MEDIAN_FEE_RATE: {format_fee_rate(stats.median_fee_rate)}"}
+                    h1{class:"text-slate-200", "MAX_FEE_RATE: {format_fee_rate(stats.max_fee_rate)}"}
+                    for percentile in stats.percentiles.iter() {
+                        h1{class:"text-slate-200", "P{percentile.percentile}_FEE_RATE: {format_fee_rate(percentile.fee_rate)}"}
+                    }
+                }
+            }
+
+            if let Some(Ok(estimate)) = &*fee_estimate.read_unchecked() {
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    h1{class:"text-slate-200 font-bold", "FEE_ESTIMATE: {estimate.transaction_size} byte transaction"}
+                    FeeRecommendationView { label: "FAST", recommendation: estimate.fast.clone() }
+                    FeeRecommendationView { label: "NORMAL", recommendation: estimate.normal.clone() }
+                    FeeRecommendationView { label: "SLOW", recommendation: estimate.slow.clone() }
+                }
+            }
+
+            FeeRateHistogram { chart_id: "fee_rate_histogram", buckets: fee_rate_histogram(&data.unconfirmed_txs) }
+
+            MempoolChart { chart_id: "mempool_bubble_chart", transactions: data.unconfirmed_txs.clone() }
+
+            if let Some(Ok(chains)) = &*mempool_chains.read_unchecked() {
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    h1{class:"text-slate-200", "TRANSACTION_CHAINS: {chains.len()}"}
+                    h1{class:"text-slate-200", "CHAINED_TRANSACTIONS: {chained_transactions(chains)}"}
+                    if !chains.is_empty() {
+                        h1{class:"text-slate-200 mt-4", "Parents are drawn left of the transactions spending their outputs, colors show the package fee rate."}
+                    }
+                }
+                if !chains.is_empty() {
+                    ChainGraph { chart_id: "mempool_chain_graph", chains: chains.clone() }
+                }
+            }
+        ),
+        None => rsx!(),
+    }
+}
+
+/* Dropped transactions listed on the page, the API returns every tracked one */
+const MAX_DROPPED_SHOWN: usize = 10;
+
+#[component]
+fn DroppedTransactionView(transaction: DroppedTransaction) -> Element {
+    let outcome = match transaction.outcome {
+        TransactionOutcome::DoubleSpent => "DOUBLE_SPENT",
+        TransactionOutcome::Confirmed => "CONFIRMED",
+        TransactionOutcome::Dropped => "DROPPED",
+    };
+    rsx!(
+        div {class:"mt-4",
+            h1{class:"text-slate-200 break-all", "TX_ID: {transaction.tx_id}"}
+            h1{class:"text-slate-200",
+                "{outcome} at {format_timestamp(transaction.left_at)}, {format_erg(transaction.fee)} ({format_fee_rate(transaction.fee_rate)})"
+            }
+        }
+    )
+}
+
+fn chained_transactions(chains: &[TransactionChain]) -> usize {
+    chains.iter().map(|chain| chain.transactions.len()).sum()
+}
+
+#[component]
+fn FeeRecommendationView(label: String, recommendation: FeeRecommendation) -> Element {
+    rsx!(
+        h1{class:"text-slate-200",
+            "{label}: {format_erg(recommendation.fee)} ({format_fee_rate(recommendation.fee_rate)}, within {recommendation.target_blocks} blocks)"
+        }
+    )
+}
+
+/* Flags a box spent by more than one mempool transaction */
+#[component]
+pub fn DoubleSpendBadge() -> Element {
+    rsx!(
+        span {class:"inline-block rounded-full bg-red-600/80 px-3 py-1 text-sm font-bold text-slate-200", "DOUBLE SPEND"}
+    )
+}
@@ -0,0 +1,67 @@
+use crate::{
+    data::{
+        stream::{use_mempool_stream, use_snapshot_resource},
+        template::get_block_template,
+    },
+    utils::{
+        chart::MempoolChart,
+        format::{format_erg, format_fee_rate, format_timestamp},
+    },
+    Route,
+};
+use dioxus::prelude::*;
+
+/* Number of template transactions listed per page */
+const TXS_PER_PAGE: usize = 20;
+
+#[component]
+pub fn ProjectedBlock() -> Element {
+    let mempool_stream = use_mempool_stream();
+    let mut page = use_signal(|| 0usize);
+    let block_template = use_snapshot_resource(mempool_stream, get_block_template);
+
+    match &*block_template.read_unchecked() {
+        Some(Ok(template)) => {
+            let page_count = template.transactions.len().div_ceil(TXS_PER_PAGE).max(1);
+            let current_page = page().min(page_count - 1);
+
+            rsx!(
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    h1{class:"text-slate-200 font-bold", "PROJECTED_NEXT_BLOCK"}
+                    h1{class:"text-slate-200", "TRANSACTIONS: {template.transactions.len()}"}
+                    h1{class:"text-slate-200", "TOTAL_SIZE: {template.total_size} / {template.max_block_size} bytes"}
+                    h1{class:"text-slate-200", "TOTAL_FEES: {format_erg(template.total_fees)}"}
+                    h1{class:"text-slate-200", "LEFT_IN_MEMPOOL: {template.left_out}"}
+                    h1{class:"text-slate-200", "SNAPSHOT_TIME: {format_timestamp(template.snapshot_time)}"}
+                }
+
+                MempoolChart { chart_id: "projected_block_chart", transactions: template.transactions.clone() }
+
+                div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                    for transaction in template.transactions.iter().skip(current_page * TXS_PER_PAGE).take(TXS_PER_PAGE) {
+                        h1{class:"text-slate-200 break-all",
+                            Link {class:"underline", to: Route::TransactionWrapper { tx_id: transaction.id.clone() }, "{transaction.id}"}
+                            " SIZE: {transaction.size} FEE_RATE: {format_fee_rate(transaction.fee_rate())}"
+                        }
+                    }
+                }
+
+                div {class:"flex justify-center items-center space-x-4 mt-6",
+                    button {class:"text-slate-200 rounded-full bg-white/30 px-4 py-1 disabled:text-gray-500",
+                        disabled: current_page == 0,
+                        onclick: move |_| page.set(current_page.saturating_sub(1)),
+                        "Previous"
+                    }
+                    h1{class:"text-slate-200", "PAGE: {current_page + 1} / {page_count}"}
+                    button {class:"text-slate-200 rounded-full bg-white/30 px-4 py-1 disabled:text-gray-500",
+                        disabled: current_page + 1 >= page_count,
+                        onclick: move |_| page.set(current_page + 1),
+                        "Next"
+                    }
+                }
+            )
+        }
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
@@ -0,0 +1,51 @@
+use crate::data::search::{search, SearchMatch, SearchResults};
+use dioxus::prelude::*;
+
+#[component]
+pub fn SearchPage(query: String) -> Element {
+    let navigator = use_navigator();
+    let search_results = use_resource(use_reactive((&query,), move |(query,)| async move {
+        let search_results = search(query).await;
+
+        /* A single match goes straight to its page */
+        if let Ok(SearchResults { matches, .. }) = &search_results {
+            if let [search_match] = matches.as_slice() {
+                navigator.replace(search_match.route());
+            }
+        }
+        search_results
+    }));
+
+    match &*search_results.read_unchecked() {
+        Some(Ok(search_results)) if search_results.matches.is_empty() => rsx!(
+            div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                h1{class:"text-slate-200 font-bold break-all", "No results for {query}"}
+                if !search_results.suggestions.is_empty() {
+                    h1{class:"text-slate-200 mt-4", "Did you mean:"}
+                    for suggestion in search_results.suggestions.iter() {
+                        SearchMatchView { search_match: suggestion.clone() }
+                    }
+                }
+            }
+        ),
+        Some(Ok(search_results)) => rsx!(
+            div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+                h1{class:"text-slate-200 font-bold break-all", "Results for {query}"}
+                for search_match in search_results.matches.iter() {
+                    SearchMatchView { search_match: search_match.clone() }
+                }
+            }
+        ),
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
+
+#[component]
+fn SearchMatchView(search_match: SearchMatch) -> Element {
+    rsx!(
+        h1{class:"text-slate-200 break-all",
+            Link {class:"underline", to: search_match.route(), "{search_match.label()}"}
+        }
+    )
+}
@@ -0,0 +1,85 @@
+use crate::{
+    data::{
+        data::Asset,
+        token::{get_token_data, TokenDetails},
+    },
+    utils::{chart::HolderChart, format::format_amount},
+    Route,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn TokenView(token_id: String) -> Element {
+    let token_data = use_resource(use_reactive((&token_id,), |(token_id,)| async move {
+        get_token_data(token_id).await
+    }));
+
+    match &*token_data.read_unchecked() {
+        Some(Ok(Some(details))) => rsx!(TokenDetailsView {
+            details: details.clone()
+        }),
+        Some(Ok(None)) => rsx!(
+            h1{class:"text-slate-200 break-all", "No token found with id {token_id}"}
+        ),
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
+
+#[component]
+fn TokenDetailsView(details: TokenDetails) -> Element {
+    rsx!(
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold break-all", "TOKEN: {details.token_id}"}
+            if let Some(name) = &details.name {
+                h1{class:"text-slate-200 break-all", "NAME: {name}"}
+            }
+            if let Some(description) = &details.description {
+                h1{class:"text-slate-200 break-all", "DESCRIPTION: {description}"}
+            }
+            h1{class:"text-slate-200", "DECIMALS: {details.decimals}"}
+            if let Some(token_type) = &details.token_type {
+                h1{class:"text-slate-200", "TYPE: {token_type}"}
+            }
+            h1{class:"text-slate-200", "EMISSION_AMOUNT: {format_amount(details.emission_amount, details.decimals)}"}
+            h1{class:"text-slate-200 break-all",
+                "MINTING_TX: "
+                Link {class:"underline", to: Route::TransactionWrapper { tx_id: details.minting_tx_id.clone() }, "{details.minting_tx_id}"}
+            }
+            h1{class:"text-slate-200 break-all",
+                "ISSUING_BOX: "
+                Link {class:"underline", to: Route::BoxWrapper { box_id: details.issuing_box_id.clone() }, "{details.issuing_box_id}"}
+            }
+            h1{class:"text-slate-200", "CREATION_HEIGHT: {details.creation_height}"}
+        }
+
+        if !details.holders.is_empty() {
+            HolderChart { chart_id: "holder_chart_{details.token_id}", holders: details.holders.clone(), decimals: details.decimals }
+        }
+
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold", "HOLDERS: {details.holders.len()}"}
+            if details.holder_boxes_capped {
+                h1{class:"text-slate-200 mb-4", "Only part of the unspent boxes holding this token were counted"}
+            }
+            for holder in details.holders.iter() {
+                h1{class:"text-slate-200 break-all",
+                    Link {class:"underline", to: Route::AddressWrapper { address: holder.address.clone() }, "{holder.address}"}
+                    ": {format_amount(holder.amount, details.decimals)}"
+                }
+            }
+        }
+    )
+}
+
+/* A token amount rendered with the token's name and decimals, linking to the token page */
+#[component]
+pub fn AssetLink(asset: Asset) -> Element {
+    rsx!(
+        h1{class:"text-slate-200 break-all",
+            "TOKEN: "
+            Link {class:"underline", to: Route::TokenWrapper { token_id: asset.token_id.clone() }, "{asset.display_name()}"}
+            " AMOUNT: {asset.display_amount()}"
+        }
+    )
+}
@@ -0,0 +1,145 @@
+use crate::{
+    data::{
+        data::{get_transaction_data, TransactionDetails},
+        lifecycle::{TransactionLifecycle, TransactionOutcome},
+    },
+    routes::{mempool::DoubleSpendBadge, token::AssetLink},
+    utils::{
+        chart::Chart,
+        format::{format_duration, format_erg, format_fee_rate, format_timestamp},
+    },
+    Route,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn TransactionView(tx_id: String) -> Element {
+    let transaction_data = use_resource(use_reactive((&tx_id,), |(tx_id,)| async move {
+        get_transaction_data(tx_id).await
+    }));
+
+    match &*transaction_data.read_unchecked() {
+        Some(Ok(Some(details))) => rsx!(TransactionDetailsView {
+            details: details.clone()
+        }),
+        Some(Ok(None)) => rsx!(
+            h1{class:"text-slate-200 break-all", "No transaction found with id {tx_id}"}
+        ),
+        Some(Err(err)) => rsx!("{err:?}"),
+        None => rsx!(),
+    }
+}
+
+#[component]
+fn TransactionDetailsView(details: TransactionDetails) -> Element {
+    let transaction = &details.transaction;
+
+    rsx!(
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold break-all", "TRANSACTION: {transaction.id}"}
+            match (details.inclusion_height, &details.block_id) {
+                (Some(height), Some(block_id)) => rsx!(
+                    h1{class:"text-slate-200", "STATUS: Confirmed"}
+                    h1{class:"text-slate-200",
+                        "BLOCK: "
+                        Link {class:"underline", to: Route::BlockVisualizerWrapper { block_height: height }, "{height}"}
+                    }
+                    h1{class:"text-slate-200 break-all", "BLOCK_ID: {block_id}"}
+                    h1{class:"text-slate-200", "CONFIRMATIONS: {details.confirmations}"}
+                ),
+                _ => rsx!(h1{class:"text-slate-200", "STATUS: Unconfirmed"}),
+            }
+            for double_spend in details.double_spends.iter() {
+                div {class:"my-2",
+                    DoubleSpendBadge {}
+                    h1{class:"text-slate-200 break-all",
+                        "CONFLICTING_INPUT: "
+                        Link {class:"underline", to: Route::BoxWrapper { box_id: double_spend.box_id.clone() }, "{double_spend.box_id}"}
+                    }
+                    for tx_id in double_spend.tx_ids.iter().filter(|tx_id| **tx_id != transaction.id) {
+                        h1{class:"text-slate-200 break-all",
+                            "CONFLICTS_WITH: "
+                            Link {class:"underline", to: Route::TransactionWrapper { tx_id: tx_id.clone() }, "{tx_id}"}
+                        }
+                    }
+                }
+            }
+            if let Some(timestamp) = details.timestamp {
+                h1{class:"text-slate-200", "TIMESTAMP: {format_timestamp(timestamp)}"}
+            }
+            if let Some(lifecycle) = &details.lifecycle {
+                h1{class:"text-slate-200", "FIRST_SEEN: {format_timestamp(lifecycle.first_seen)}"}
+                h1{class:"text-slate-200", "{lifecycle_summary(lifecycle)}"}
+            }
+            h1{class:"text-slate-200", "SIZE: {transaction.size}"}
+            h1{class:"text-slate-200", "VALUE: {format_erg(transaction.output_value())}"}
+            h1{class:"text-slate-200", "FEE: {format_erg(transaction.fee())}"}
+            h1{class:"text-slate-200", "FEE_RATE: {format_fee_rate(transaction.fee_rate())}"}
+        }
+
+        Chart { chart_id: "tx_chart_{transaction.id}", transactions: vec![transaction.clone()] }
+
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold", "INPUTS: {transaction.inputs.len()}"}
+            for input in transaction.inputs.iter() {
+                div {class:"mb-4",
+                    h1{class:"text-slate-200 break-all",
+                        "BOX_ID: "
+                        Link {class:"underline", to: Route::BoxWrapper { box_id: input.box_id.clone() }, "{input.box_id}"}
+                    }
+                    match input.value {
+                        Some(value) => rsx!{ h1{class:"text-slate-200", "VALUE: {format_erg(value)}"} },
+                        None => rsx!{ h1{class:"text-slate-200", "VALUE: unknown"} },
+                    }
+                }
+            }
+            if !transaction.data_inputs.is_empty() {
+                h1{class:"text-slate-200 font-bold", "DATA_INPUTS: {transaction.data_inputs.len()}"}
+                for data_input in transaction.data_inputs.iter() {
+                    h1{class:"text-slate-200 break-all mb-4",
+                        "BOX_ID: "
+                        Link {class:"underline", to: Route::BoxWrapper { box_id: data_input.box_id.clone() }, "{data_input.box_id}"}
+                    }
+                }
+            }
+        }
+
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-4 mt-6",
+            h1{class:"text-slate-200 font-bold", "OUTPUTS: {transaction.outputs.len()}"}
+            for output in transaction.outputs.iter() {
+                div {class:"mb-4",
+                    h1{class:"text-slate-200 break-all",
+                        "BOX_ID: "
+                        Link {class:"underline", to: Route::BoxWrapper { box_id: output.box_id.clone() }, "{output.box_id}"}
+                    }
+                    h1{class:"text-slate-200", "INDEX: {output.index}"}
+                    h1{class:"text-slate-200", "VALUE: {format_erg(output.value)}"}
+                    h1{class:"text-slate-200", "CREATION_HEIGHT: {output.creation_height}"}
+                    h1{class:"text-slate-200 break-all", "ERGO_TREE: {output.ergo_tree}"}
+                    for asset in output.assets.iter() {
+                        AssetLink { asset: asset.clone() }
+                    }
+                    for (register, value) in output.additional_registers.iter() {
+                        h1{class:"text-slate-200 break-all", "{register}: {value}"}
+                    }
+                }
+            }
+        }
+    )
+}
+
+fn lifecycle_summary(lifecycle: &TransactionLifecycle) -> String {
+    let time_in_mempool = format_duration(lifecycle.time_in_mempool());
+    match lifecycle.outcome {
+        Some(TransactionOutcome::Confirmed) => {
+            format!("Seen {} before confirmation", time_in_mempool)
+        }
+        Some(TransactionOutcome::DoubleSpent) => {
+            format!("Double spent after {} in the mempool", time_in_mempool)
+        }
+        Some(TransactionOutcome::Dropped) => {
+            format!("Dropped from the mempool after {}", time_in_mempool)
+        }
+        None => format!("In the mempool for {}", time_in_mempool),
+    }
+}
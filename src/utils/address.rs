@@ -0,0 +1,95 @@
+use blake2::{digest::consts::U32, Blake2b, Digest};
+
+/* Length of the Blake2b-256 checksum appended to every encoded address */
+const CHECKSUM_LENGTH: usize = 4;
+
+/* Address types, stored in the low nibble of the prefix byte */
+const P2PK_ADDRESS: u8 = 0x01;
+const P2SH_ADDRESS: u8 = 0x02;
+const P2S_ADDRESS: u8 = 0x03;
+
+/* Network types, stored in the high nibble of the prefix byte */
+const MAINNET: u8 = 0x00;
+const TESTNET: u8 = 0x10;
+
+/* Check that a string is a base58 encoded Ergo address with a valid prefix and checksum */
+pub fn is_valid_address(address: &str) -> bool {
+    let Ok(bytes) = bs58::decode(address).into_vec() else {
+        return false;
+    };
+    if bytes.len() <= 1 + CHECKSUM_LENGTH {
+        return false;
+    }
+
+    let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LENGTH);
+    if Blake2b::<U32>::digest(body)[..CHECKSUM_LENGTH] != *checksum {
+        return false;
+    }
+
+    let prefix = body[0];
+    let content_length = body.len() - 1;
+    let valid_network = matches!(prefix & 0xF0, MAINNET | TESTNET);
+    let valid_content = match prefix & 0x0F {
+        /* Compressed public key */
+        P2PK_ADDRESS => content_length == 33,
+        /* First 192 bits of the script hash */
+        P2SH_ADDRESS => content_length == 24,
+        P2S_ADDRESS => true,
+        _ => false,
+    };
+    valid_network && valid_content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(prefix: u8, content: &[u8]) -> String {
+        let mut bytes = vec![prefix];
+        bytes.extend_from_slice(content);
+        let checksum = Blake2b::<U32>::digest(&bytes);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_LENGTH]);
+        bs58::encode(bytes).into_string()
+    }
+
+    #[test]
+    fn accepts_known_addresses() {
+        assert!(is_valid_address(
+            "9fRAWhdxEsTcdb8PhGNrZfwqa65zfkuYHAMmkQLcic1gdLSV5vA"
+        ));
+        assert!(is_valid_address(
+            "9fRusAarL1KkrWQVsxSRVYnvWxaAT2A96cKtNn9tvPh5XUyCisr"
+        ));
+        /* Testnet */
+        assert!(is_valid_address(
+            "3WvsT2Gm4EpsM9Pg18PdY6XyhNNMqXDsvJTbbf6ihLvAmSb7u5RN"
+        ));
+    }
+
+    #[test]
+    fn checks_the_content_length_of_each_address_type() {
+        assert!(is_valid_address(&encode(MAINNET | P2SH_ADDRESS, &[7; 24])));
+        assert!(is_valid_address(&encode(MAINNET | P2S_ADDRESS, &[7; 60])));
+        assert!(is_valid_address(&encode(TESTNET | P2PK_ADDRESS, &[7; 33])));
+
+        assert!(!is_valid_address(&encode(MAINNET | P2PK_ADDRESS, &[7; 32])));
+        assert!(!is_valid_address(&encode(MAINNET | P2SH_ADDRESS, &[7; 33])));
+        /* Unknown network and address type */
+        assert!(!is_valid_address(&encode(0x20 | P2PK_ADDRESS, &[7; 33])));
+        assert!(!is_valid_address(&encode(MAINNET | 0x04, &[7; 33])));
+    }
+
+    #[test]
+    fn rejects_bad_checksums_and_encodings() {
+        /* Last character changed */
+        assert!(!is_valid_address(
+            "9fRAWhdxEsTcdb8PhGNrZfwqa65zfkuYHAMmkQLcic1gdLSV5vB"
+        ));
+        assert!(!is_valid_address(""));
+        assert!(!is_valid_address("9f"));
+        /* 0, O, I and l are not in the base58 alphabet */
+        assert!(!is_valid_address(
+            "0fRAWhdxEsTcdb8PhGNrZfwqa65zfkuYHAMmkQLcic1gdLSV5vA"
+        ));
+    }
+}
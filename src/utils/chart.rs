@@ -1,62 +1,591 @@
 use charming::{
-    component::Legend,
-    series::{Sankey, SankeyLink},
-    Chart, WasmRenderer,
+    component::{Axis, Legend},
+    element::{AreaStyle, AxisType, Color, ColorBy, Tooltip, Trigger},
+    series::{
+        Bar, Graph, GraphCategory, GraphData, GraphLayout, GraphLink, GraphNode, Line, Pie, Sankey,
+        SankeyLink,
+    },
+    Chart, Echarts, WasmRenderer,
 };
 use dioxus::prelude::*;
 use dioxus_logger::tracing;
+use std::{
+    cell::{OnceCell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::{
+    data::{
+        address::BalancePoint,
+        chains::TransactionChain,
+        data::UnconfirmedTxs,
+        fees::{fee_rate_bucket, fee_rate_buckets, FeeRateBucket, FEE_RATE_BANDS},
+        token::TokenHolder,
+    },
+    utils::format::{format_fee_rate, format_timestamp, ERG_DECIMALS},
+    Route,
+};
+
+/* Event handling is not exposed by charming's Echarts binding, ChartInstance is the same object */
+#[wasm_bindgen]
+extern "C" {
+    type ChartInstance;
+
+    type ChartClick;
+
+    #[wasm_bindgen(method, getter, js_name = dataIndex)]
+    fn data_index(this: &ChartClick) -> usize;
+
+    /* "node" or "edge" for graph series */
+    #[wasm_bindgen(method, getter, js_name = dataType)]
+    fn data_type(this: &ChartClick) -> Option<String>;
+
+    #[wasm_bindgen(method, js_name = on)]
+    fn on(this: &ChartInstance, event: &str, handler: &Closure<dyn FnMut(ChartClick)>);
+
+    #[wasm_bindgen(method, js_name = off)]
+    fn off(this: &ChartInstance, event: &str);
+}
+
+/* Sankey node names must be unique, boxes and transactions are labeled by their id prefix */
+fn box_node(box_id: &str) -> String {
+    format!("box {}", &box_id[..box_id.len().min(8)])
+}
+
+fn transaction_node(tx_id: &str) -> String {
+    format!("tx {}", &tx_id[..tx_id.len().min(8)])
+}
+
+fn add_node(nodes: &mut Vec<String>, name: String) {
+    if !nodes.contains(&name) {
+        nodes.push(name);
+    }
+}
+
+/* Build the input boxes -> transaction -> output boxes flow, weighted by nanoERG value.
+ * Inputs whose value could not be resolved share what is left of the outputs' total evenly. */
+fn value_flow(transactions: &[UnconfirmedTxs]) -> (Vec<String>, Vec<SankeyLink>) {
+    let mut nodes: Vec<String> = vec![];
+    let mut links: Vec<SankeyLink> = vec![];
+
+    for transaction in transactions {
+        let tx_node = transaction_node(&transaction.id);
+        add_node(&mut nodes, tx_node.clone());
+
+        let outputs_total = transaction.output_value();
+        let inputs_known: u64 = transaction
+            .inputs
+            .iter()
+            .filter_map(|input| input.value)
+            .sum();
+        let inputs_unknown = transaction
+            .inputs
+            .iter()
+            .filter(|input| input.value.is_none())
+            .count();
+        let unknown_share = match inputs_unknown {
+            0 => 0.0,
+            count => outputs_total.saturating_sub(inputs_known) as f64 / count as f64,
+        };
+
+        for input in transaction.inputs.iter() {
+            let input_node = box_node(&input.box_id);
+            add_node(&mut nodes, input_node.clone());
+            let value = match input.value {
+                Some(value) => value as f64,
+                None => unknown_share,
+            };
+            links.push(SankeyLink::from((input_node, tx_node.clone(), value)));
+        }
+
+        for output in transaction.outputs.iter() {
+            let output_node = box_node(&output.box_id);
+            add_node(&mut nodes, output_node.clone());
+            links.push(SankeyLink::from((
+                tx_node.clone(),
+                output_node,
+                output.value as f64,
+            )));
+        }
+    }
+
+    (nodes, links)
+}
 
 #[component]
-pub fn Chart() -> Element {
+pub fn Chart(chart_id: String, transactions: Vec<UnconfirmedTxs>) -> Element {
+    let render_id = chart_id.clone();
     spawn(async move {
+        let (nodes, links) = value_flow(&transactions);
         let chart = Chart::new().legend(Legend::new().top("bottom")).series(
             Sankey::new()
-                .name("Block")
-                .data(vec!["1", "2", "3", "a1", "b2", "c3", "block"])
-                .links(vec![
-                    SankeyLink {
-                        source: "1".to_string(),
-                        target: "block".to_string(),
-                        value: 5.0,
-                    },
-                    SankeyLink {
-                        source: "2".to_string(),
-                        target: "block".to_string(),
-                        value: 3.0,
-                    },
-                    SankeyLink {
-                        source: "3".to_string(),
-                        target: "block".to_string(),
-                        value: 2.0,
-                    },
-                    SankeyLink {
-                        source: "block".to_string(),
-                        target: "a1".to_string(),
-                        value: 2.0,
-                    },
-                    SankeyLink {
-                        source: "block".to_string(),
-                        target: "b2".to_string(),
-                        value: 6.0,
-                    },
-                    SankeyLink {
-                        source: "block".to_string(),
-                        target: "c3".to_string(),
-                        value: 2.0,
-                    },
-                ]),
+                .name("Value flow (nanoERG)")
+                .data(nodes)
+                .links(links),
         );
         let renderer = WasmRenderer::new(1000, 800);
 
-        renderer.render("chart", &chart).unwrap();
-        tracing::info!("rendered chart");
+        match renderer.render(&render_id, &chart) {
+            Ok(_) => tracing::info!("rendered chart"),
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
+    });
+
+    rsx! (
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
+            style: "width: 100%; text-align: center;",
+            div {
+                id: "{chart_id}",
+                style: "display: inline-block;",
+            }
+        }
+    )
+}
+
+#[component]
+pub fn BalanceChart(chart_id: String, points: Vec<BalancePoint>) -> Element {
+    let render_id = chart_id.clone();
+    spawn(async move {
+        let dates: Vec<String> = points
+            .iter()
+            .map(|point| format_timestamp(point.timestamp))
+            .collect();
+        let balances: Vec<f64> = points
+            .iter()
+            .map(|point| point.nano_ergs as f64 / 10f64.powi(ERG_DECIMALS as i32))
+            .collect();
+
+        let chart = Chart::new()
+            .tooltip(Tooltip::new().trigger(Trigger::Axis))
+            .x_axis(Axis::new().type_(AxisType::Category).data(dates))
+            .y_axis(Axis::new().type_(AxisType::Value).name("ERG"))
+            .series(Line::new().name("Balance (ERG)").data(balances));
+        let renderer = WasmRenderer::new(1000, 400);
+
+        match renderer.render(&render_id, &chart) {
+            Ok(_) => tracing::info!("rendered chart"),
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
+    });
+
+    rsx! (
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
+            style: "width: 100%; text-align: center;",
+            div {
+                id: "{chart_id}",
+                style: "display: inline-block;",
+            }
+        }
+    )
+}
+
+/* One value over time, drawn as a filled line */
+#[component]
+pub fn TimeSeriesChart(
+    chart_id: String,
+    name: String,
+    timestamps: Vec<u64>,
+    values: Vec<f64>,
+) -> Element {
+    let render_id = chart_id.clone();
+    let series_name = name.clone();
+    spawn(async move {
+        let dates: Vec<String> = timestamps
+            .iter()
+            .map(|timestamp| format_timestamp(*timestamp))
+            .collect();
+
+        let chart = Chart::new()
+            .tooltip(Tooltip::new().trigger(Trigger::Axis))
+            .x_axis(Axis::new().type_(AxisType::Category).data(dates))
+            .y_axis(Axis::new().type_(AxisType::Value))
+            .series(
+                Line::new()
+                    .name(series_name)
+                    .show_symbol(false)
+                    .area_style(AreaStyle::new())
+                    .data(values),
+            );
+        let renderer = WasmRenderer::new(1000, 300);
+
+        match renderer.render(&render_id, &chart) {
+            Ok(_) => tracing::info!("rendered chart"),
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
+    });
+
+    rsx! (
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
+            style: "width: 100%; text-align: center;",
+            h1{class:"text-slate-200 font-bold", "{name}"}
+            div {
+                id: "{chart_id}",
+                style: "display: inline-block;",
+            }
+        }
+    )
+}
+
+/* Holders beyond this rank are grouped into a single "Other" slice */
+const HOLDER_CHART_SLICES: usize = 10;
+
+#[component]
+pub fn HolderChart(chart_id: String, holders: Vec<TokenHolder>, decimals: u32) -> Element {
+    let render_id = chart_id.clone();
+    spawn(async move {
+        let scale = 10f64.powi(decimals as i32);
+        let mut slices: Vec<(f64, String)> = holders
+            .iter()
+            .take(HOLDER_CHART_SLICES)
+            .map(|holder| {
+                let label = format!("{}...", &holder.address[..holder.address.len().min(8)]);
+                (holder.amount as f64 / scale, label)
+            })
+            .collect();
+        let other: u64 = holders
+            .iter()
+            .skip(HOLDER_CHART_SLICES)
+            .map(|holder| holder.amount)
+            .sum();
+        if other > 0 {
+            slices.push((other as f64 / scale, "Other".to_string()));
+        }
+
+        let chart = Chart::new()
+            .tooltip(Tooltip::new().trigger(Trigger::Item))
+            .series(Pie::new().name("Holders").radius("65%").data(slices));
+        let renderer = WasmRenderer::new(1000, 500);
+
+        match renderer.render(&render_id, &chart) {
+            Ok(_) => tracing::info!("rendered chart"),
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
+    });
+
+    rsx! (
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
+            style: "width: 100%; text-align: center;",
+            div {
+                id: "{chart_id}",
+                style: "display: inline-block;",
+            }
+        }
+    )
+}
+
+type ClickHandler = Closure<dyn FnMut(ChartClick)>;
+
+/* Click handler of one chart, created on the first render and dropped with the component.
+ * Renders only swap the tx ids it opens. */
+#[derive(Clone)]
+struct TransactionClick {
+    navigator: Navigator,
+    tx_ids: Rc<RefCell<Vec<String>>>,
+    handler: Rc<OnceCell<ClickHandler>>,
+    chart: Rc<RefCell<Option<ChartInstance>>>,
+}
+
+impl TransactionClick {
+    /* Clicking the data item at index i opens the transaction tx_ids[i], edges are ignored */
+    fn bind(&self, echarts: Echarts, tx_ids: Vec<String>) {
+        *self.tx_ids.borrow_mut() = tx_ids;
+        let handler = self.handler.get_or_init(|| {
+            let navigator = self.navigator;
+            let tx_ids = self.tx_ids.clone();
+            Closure::new(move |click: ChartClick| {
+                if click
+                    .data_type()
+                    .is_some_and(|data_type| data_type != "node")
+                {
+                    return;
+                }
+                let tx_id = tx_ids.borrow().get(click.data_index()).cloned();
+                if let Some(tx_id) = tx_id {
+                    navigator.push(Route::TransactionWrapper { tx_id });
+                }
+            })
+        });
+
+        /* The chart instance is reused across renders, drop the previous binding */
+        let chart: ChartInstance = echarts.unchecked_into();
+        chart.off("click");
+        chart.on("click", handler);
+        *self.chart.borrow_mut() = Some(chart);
+    }
+}
+
+fn use_transaction_click() -> TransactionClick {
+    let navigator = use_navigator();
+    let click = use_hook(|| TransactionClick {
+        navigator,
+        tx_ids: Rc::default(),
+        handler: Rc::default(),
+        chart: Rc::default(),
+    });
+    let unbind = click.clone();
+    use_drop(move || {
+        if let Some(chart) = unbind.chart.borrow_mut().take() {
+            chart.off("click");
+        }
+    });
+    click
+}
+
+const MEMPOOL_CHART_WIDTH: f64 = 1000.0;
+
+/* Bubble diameter in pixels per square root of a byte, keeping areas proportional to size */
+const BUBBLE_SCALE: f64 = 1.5;
+const BUBBLE_MIN_DIAMETER: f64 = 6.0;
+const BUBBLE_MAX_DIAMETER: f64 = 200.0;
+
+fn bubble_diameter(size: u32) -> f64 {
+    ((size as f64).sqrt() * BUBBLE_SCALE).clamp(BUBBLE_MIN_DIAMETER, BUBBLE_MAX_DIAMETER)
+}
+
+fn fee_rate_band(fee_rate: f64) -> u64 {
+    fee_rate_bucket(fee_rate) as u64
+}
+
+fn fee_rate_band_colors() -> Vec<Color> {
+    FEE_RATE_BANDS
+        .iter()
+        .map(|(_, color)| (*color).into())
+        .collect()
+}
+
+/* Pack the bubbles left to right in rows, highest fee rate first like a miner would pick them.
+ * Returns the nodes in the same order as the transaction ids and the height of the rows. */
+fn bubble_layout(transactions: &[UnconfirmedTxs]) -> (Vec<GraphNode>, Vec<String>, f64) {
+    let mut transactions: Vec<&UnconfirmedTxs> = transactions.iter().collect();
+    transactions.sort_by(|a, b| b.fee_rate().total_cmp(&a.fee_rate()));
+
+    let mut nodes: Vec<GraphNode> = vec![];
+    let mut tx_ids: Vec<String> = vec![];
+    let (mut x, mut row_top, mut row_height) = (0.0, 0.0, 0.0f64);
+
+    for transaction in transactions {
+        let diameter = bubble_diameter(transaction.size);
+        if x + diameter > MEMPOOL_CHART_WIDTH && x > 0.0 {
+            x = 0.0;
+            row_top += row_height;
+            row_height = 0.0;
+        }
+        row_height = row_height.max(diameter);
+
+        let fee_rate = transaction.fee_rate();
+        nodes.push(GraphNode {
+            id: transaction.id.clone(),
+            name: format!(
+                "{}...<br/>SIZE: {} bytes<br/>FEE_RATE: {}",
+                &transaction.id[..transaction.id.len().min(16)],
+                transaction.size,
+                format_fee_rate(fee_rate)
+            ),
+            x: x + diameter / 2.0,
+            y: row_top + diameter / 2.0,
+            value: transaction.size as f64,
+            category: fee_rate_band(fee_rate),
+            symbol_size: diameter,
+            label: None,
+        });
+        tx_ids.push(transaction.id.clone());
+        x += diameter;
+    }
+
+    (nodes, tx_ids, row_top + row_height)
+}
+
+/* Graph positioned by the caller, nodes colored by the FEE_RATE_BANDS category they are in */
+fn fee_rate_graph_chart(name: &str, nodes: Vec<GraphNode>, links: Vec<GraphLink>) -> Chart {
+    let band_names: Vec<String> = fee_rate_buckets()
+        .iter()
+        .map(|bucket| format!("{} nanoERG/byte", bucket.label()))
+        .collect();
+    let categories: Vec<GraphCategory> = band_names
+        .iter()
+        .map(|name| GraphCategory { name: name.clone() })
+        .collect();
+
+    Chart::new()
+        .color(fee_rate_band_colors())
+        .tooltip(Tooltip::new().trigger(Trigger::Item).formatter("{b}"))
+        .legend(Legend::new().top("bottom").data(band_names))
+        .series(
+            Graph::new()
+                .name(name)
+                .layout(GraphLayout::None)
+                .roam(true)
+                .data(GraphData {
+                    nodes,
+                    links,
+                    categories,
+                }),
+        )
+}
+
+/* Every transaction as a bubble sized by its bytes and colored by its fee rate band,
+ * clicking a bubble opens the transaction page */
+#[component]
+pub fn MempoolChart(chart_id: String, transactions: Vec<UnconfirmedTxs>) -> Element {
+    let click = use_transaction_click();
+    let render_id = chart_id.clone();
+    spawn(async move {
+        let (nodes, tx_ids, height) = bubble_layout(&transactions);
+        let chart = fee_rate_graph_chart("Mempool", nodes, vec![]);
+        /* Leave room for the legend below the bubbles */
+        let renderer =
+            WasmRenderer::new(MEMPOOL_CHART_WIDTH as u32, height.max(200.0) as u32 + 100);
+
+        match renderer.render(&render_id, &chart) {
+            Ok(echarts) => {
+                click.bind(echarts, tx_ids);
+                tracing::info!("rendered chart")
+            }
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
+    });
+
+    rsx! (
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
+            style: "width: 100%; text-align: center;",
+            div {
+                id: "{chart_id}",
+                style: "display: inline-block;",
+            }
+        }
+    )
+}
+
+/* Horizontal and vertical distance between two transactions of the chain graph */
+const CHAIN_COLUMN_WIDTH: f64 = 120.0;
+const CHAIN_ROW_HEIGHT: f64 = 50.0;
+
+/* Chains stacked top to bottom, each transaction in the column of its depth so that
+ * parents are left of their children. Returns the nodes, their ids and the total height. */
+fn chain_layout(chains: &[TransactionChain]) -> (Vec<GraphNode>, Vec<String>, f64) {
+    let mut nodes: Vec<GraphNode> = vec![];
+    let mut tx_ids: Vec<String> = vec![];
+    let mut chain_top = 0.0;
+
+    for chain in chains {
+        let mut column_rows: Vec<u32> = vec![];
+        for transaction in chain.transactions.iter() {
+            let depth = transaction.depth as usize;
+            if column_rows.len() <= depth {
+                column_rows.resize(depth + 1, 0);
+            }
+            let row = column_rows[depth];
+            column_rows[depth] += 1;
+
+            nodes.push(GraphNode {
+                id: transaction.tx_id.clone(),
+                name: format!(
+                    "{}...<br/>FEE_RATE: {}<br/>PACKAGE_FEE_RATE: {}<br/>ANCESTORS: {} DESCENDANTS: {}",
+                    &transaction.tx_id[..transaction.tx_id.len().min(16)],
+                    format_fee_rate(transaction.fee_rate),
+                    format_fee_rate(transaction.package_fee_rate),
+                    transaction.ancestors.len(),
+                    transaction.descendants.len()
+                ),
+                x: depth as f64 * CHAIN_COLUMN_WIDTH,
+                y: chain_top + row as f64 * CHAIN_ROW_HEIGHT,
+                value: transaction.size as f64,
+                category: fee_rate_band(transaction.package_fee_rate),
+                symbol_size: bubble_diameter(transaction.size).min(CHAIN_ROW_HEIGHT * 0.8),
+                label: None,
+            });
+            tx_ids.push(transaction.tx_id.clone());
+        }
+        /* One empty row between two chains */
+        chain_top +=
+            (column_rows.iter().max().copied().unwrap_or_default() + 1) as f64 * CHAIN_ROW_HEIGHT;
+    }
+
+    (nodes, tx_ids, chain_top)
+}
+
+/* Unconfirmed transactions spending each other's outputs, colored by package fee rate */
+#[component]
+pub fn ChainGraph(chart_id: String, chains: Vec<TransactionChain>) -> Element {
+    let click = use_transaction_click();
+    let render_id = chart_id.clone();
+    spawn(async move {
+        let (nodes, tx_ids, height) = chain_layout(&chains);
+        let links: Vec<GraphLink> = chains
+            .iter()
+            .flat_map(|chain| chain.links.iter())
+            .map(|link| GraphLink {
+                source: link.parent.clone(),
+                target: link.child.clone(),
+                value: None,
+            })
+            .collect();
+
+        let chart = fee_rate_graph_chart("Transaction chains", nodes, links);
+        /* Leave room for the legend below the chains */
+        let renderer = WasmRenderer::new(1000, height.clamp(200.0, 800.0) as u32 + 100);
+
+        match renderer.render(&render_id, &chart) {
+            Ok(echarts) => {
+                click.bind(echarts, tx_ids);
+                tracing::info!("rendered chart")
+            }
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
+    });
+
+    rsx! (
+        div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
+            style: "width: 100%; text-align: center;",
+            div {
+                id: "{chart_id}",
+                style: "display: inline-block;",
+            }
+        }
+    )
+}
+
+#[component]
+pub fn FeeRateHistogram(chart_id: String, buckets: Vec<FeeRateBucket>) -> Element {
+    let render_id = chart_id.clone();
+    spawn(async move {
+        let labels: Vec<String> = buckets.iter().map(|bucket| bucket.label()).collect();
+        let counts: Vec<i64> = buckets
+            .iter()
+            .map(|bucket| bucket.transaction_count as i64)
+            .collect();
+
+        /* One bucket per fee rate band, bars colored like the bubbles */
+        let chart = Chart::new()
+            .color(fee_rate_band_colors())
+            .tooltip(Tooltip::new().trigger(Trigger::Axis))
+            .x_axis(
+                Axis::new()
+                    .type_(AxisType::Category)
+                    .name("nanoERG/byte")
+                    .data(labels),
+            )
+            .y_axis(Axis::new().type_(AxisType::Value).name("Transactions"))
+            .series(
+                Bar::new()
+                    .name("Transactions")
+                    .color_by(ColorBy::Data)
+                    .data(counts),
+            );
+        let renderer = WasmRenderer::new(1000, 400);
+
+        match renderer.render(&render_id, &chart) {
+            Ok(_) => tracing::info!("rendered chart"),
+            Err(err) => tracing::error!("failed to render chart: {:?}", err),
+        }
     });
 
     rsx! (
         div {class:"bg-opacity-10 bg-white backdrop-filter backdrop-blur-2xl rounded-lg shadow-lg p-2 mt-6",
             style: "width: 100%; text-align: center;",
             div {
-                id: "chart",
+                id: "{chart_id}",
                 style: "display: inline-block;",
             }
         }
@@ -0,0 +1,138 @@
+/* Number of decimals of the native ERG token, 1 ERG = 10^9 nanoERG */
+pub const ERG_DECIMALS: u32 = 9;
+
+/* Most decimals an amount can use, 10^19 already exceeds any u64 amount */
+pub const MAX_DECIMALS: u32 = 19;
+
+/* Format a raw integer amount with the given number of decimals, trimming trailing zeros.
+ * Used for nanoERG values and for token amounts with the token's registered decimals.
+ * Decimals come from token metadata anyone can mint, they are clamped to MAX_DECIMALS. */
+pub fn format_amount(amount: u64, decimals: u32) -> String {
+    let decimals = decimals.min(MAX_DECIMALS);
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let divisor = 10u128.pow(decimals);
+    let whole = amount as u128 / divisor;
+    let fraction = amount as u128 % divisor;
+
+    if fraction == 0 {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+pub fn format_erg(nano_ergs: u64) -> String {
+    format!("{} ERG", format_amount(nano_ergs, ERG_DECIMALS))
+}
+
+/* Fee rates are fractional nanoERG per byte, whole units are precise enough to compare */
+pub fn format_fee_rate(fee_rate: f64) -> String {
+    format!("{:.0} nanoERG/byte", fee_rate)
+}
+
+/* Format a unix timestamp in milliseconds as a UTC date and time */
+pub fn format_timestamp(timestamp_ms: u64) -> String {
+    let seconds = timestamp_ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+
+    /* Days since 1970-01-01 to a civil date, see http://howardhinnant.github.io/date_algorithms.html */
+    let days = (timestamp_ms / 1000 / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year, month, day, hours, minutes, seconds
+    )
+}
+
+/* A duration in milliseconds in its largest whole unit, e.g. "4 min" */
+pub fn format_duration(duration_ms: u64) -> String {
+    let seconds = duration_ms / 1000;
+    match seconds {
+        0..=59 => format!("{} s", seconds),
+        60..=3599 => format!("{} min", seconds / 60),
+        3600..=86399 => format!("{} h", seconds / 3600),
+        _ => format!("{} d", seconds / 86400),
+    }
+}
+
+/* Time elapsed between two unix timestamps in milliseconds, e.g. "4 min ago" */
+pub fn format_age(timestamp_ms: u64, now_ms: u64) -> String {
+    format!(
+        "{} ago",
+        format_duration(now_ms.saturating_sub(timestamp_ms))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_amounts() {
+        assert_eq!(format_amount(1234, 0), "1234");
+        assert_eq!(format_amount(0, 9), "0");
+        assert_eq!(format_amount(1_500_000_000, 9), "1.5");
+        assert_eq!(format_amount(2_000_000_000, 9), "2");
+        assert_eq!(format_amount(1_000_001, 9), "0.001000001");
+        assert_eq!(format_amount(u64::MAX, 0), "18446744073709551615");
+        assert_eq!(format_amount(u64::MAX, 9), "18446744073.709551615");
+        assert_eq!(format_amount(u64::MAX, 19), "1.8446744073709551615");
+    }
+
+    #[test]
+    fn clamps_decimals() {
+        assert_eq!(
+            format_amount(u64::MAX, 39),
+            format_amount(u64::MAX, MAX_DECIMALS)
+        );
+        assert_eq!(format_amount(5, 2_000_000_000), "0.0000000000000000005");
+        assert_eq!(format_amount(5, u32::MAX), format_amount(5, MAX_DECIMALS));
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(
+            format_timestamp(1_700_000_000_999),
+            "2023-11-14 22:13:20 UTC"
+        );
+        /* Leap day */
+        assert_eq!(
+            format_timestamp(1_709_164_800_000),
+            "2024-02-29 00:00:00 UTC"
+        );
+        assert_eq!(
+            format_timestamp(1_709_251_199_000),
+            "2024-02-29 23:59:59 UTC"
+        );
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(999), "0 s");
+        assert_eq!(format_duration(59_999), "59 s");
+        assert_eq!(format_duration(60_000), "1 min");
+        assert_eq!(format_duration(3_599_999), "59 min");
+        assert_eq!(format_duration(3_600_000), "1 h");
+        assert_eq!(format_duration(86_400_000 * 3), "3 d");
+        assert_eq!(format_age(1_000, 241_000), "4 min ago");
+        assert_eq!(format_age(5_000, 1_000), "0 s ago");
+    }
+}
@@ -0,0 +1,501 @@
+use std::fmt;
+
+/* Type codes of the sigma constant serialization format */
+const PRIM_RANGE: u8 = 12;
+const TUPLE_TYPE_CODE: u8 = 96;
+
+const COLL_CONSTR: u8 = 1;
+const NESTED_COLL_CONSTR: u8 = 2;
+const OPTION_CONSTR: u8 = 3;
+const OPTION_COLL_CONSTR: u8 = 4;
+const PAIR1_CONSTR: u8 = 5;
+const PAIR2_CONSTR: u8 = 6;
+const PAIR_SYMMETRIC_CONSTR: u8 = 7;
+
+/* Sigma proposition op codes */
+const PROVE_DLOG: u8 = 0xcd;
+const PROVE_DH_TUPLE: u8 = 0xce;
+const TRIVIAL_FALSE: u8 = 0x7e;
+const TRIVIAL_TRUE: u8 = 0x7f;
+const CAND: u8 = 0x96;
+const COR: u8 = 0x97;
+const CTHRESHOLD: u8 = 0x98;
+
+/* Compressed secp256k1 point */
+const GROUP_ELEMENT_LENGTH: usize = 33;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SType {
+    Boolean,
+    Byte,
+    Short,
+    Int,
+    Long,
+    BigInt,
+    GroupElement,
+    SigmaProp,
+    Unit,
+    Coll(Box<SType>),
+    Option(Box<SType>),
+    Tuple(Vec<SType>),
+    /* Types that can appear in a register but whose values are not decoded */
+    Other(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SValue {
+    Boolean(bool),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    /* Big-endian two's complement */
+    BigInt(Vec<u8>),
+    GroupElement(Vec<u8>),
+    /* Serialized sigma proposition */
+    SigmaProp(Vec<u8>),
+    Unit,
+    /* Coll[Byte], kept apart from Coll so it renders as hex */
+    Bytes(Vec<u8>),
+    Coll(Vec<SValue>),
+    Option(Option<Box<SValue>>),
+    Tuple(Vec<SValue>),
+}
+
+/* A register value: its type and the value decoded from it */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub tpe: SType,
+    pub value: SValue,
+}
+
+impl Constant {
+    /* UTF-8 text held in a Coll[Byte], as used by EIP-4 token registers */
+    pub fn as_text(&self) -> Option<String> {
+        match &self.value {
+            SValue::Bytes(bytes) => String::from_utf8(bytes.clone())
+                .ok()
+                .filter(|text| !text.chars().any(char::is_control)),
+            _ => None,
+        }
+    }
+}
+
+/* Decode a hex encoded register constant */
+pub fn decode_constant(serialized: &str) -> Result<Constant, String> {
+    let bytes = decode_hex(serialized)?;
+    let mut reader = Reader {
+        bytes: &bytes,
+        position: 0,
+    };
+
+    let tpe = reader.read_type()?;
+    let value = reader.read_value(&tpe)?;
+    if reader.position != bytes.len() {
+        return Err(format!(
+            "{} trailing bytes after constant",
+            bytes.len() - reader.position
+        ));
+    }
+    Ok(Constant { tpe, value })
+}
+
+fn decode_hex(serialized: &str) -> Result<Vec<u8>, String> {
+    if !serialized.len().is_multiple_of(2) || !serialized.is_ascii() {
+        return Err("invalid hex string".to_string());
+    }
+    (0..serialized.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&serialized[index..index + 2], 16)
+                .map_err(|_| "invalid hex string".to_string())
+        })
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or("unexpected end of constant")?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /* Lengths are read from the constant itself, never trust them past the bytes left */
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if length > self.remaining() {
+            return Err("unexpected end of constant".to_string());
+        }
+        let bytes = &self.bytes[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /* Unsigned variable-length quantity, 7 bits per byte, least significant group first */
+    fn vlq(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("variable-length integer too long".to_string())
+    }
+
+    /* Signed integers are ZigZag encoded before being written as a VLQ */
+    fn zigzag(&mut self) -> Result<i64, String> {
+        let value = self.vlq()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    fn length(&mut self) -> Result<usize, String> {
+        Ok(self.vlq()? as usize)
+    }
+
+    fn read_type(&mut self) -> Result<SType, String> {
+        let code = self.byte()?;
+        match code {
+            1..=11 => embeddable_type(code),
+            PRIM_RANGE..=95 => {
+                let (constructor, prim) = (code / PRIM_RANGE, code % PRIM_RANGE);
+                let element = |reader: &mut Self| match prim {
+                    0 => reader.read_type(),
+                    prim => embeddable_type(prim),
+                };
+                match constructor {
+                    COLL_CONSTR => Ok(SType::Coll(Box::new(element(self)?))),
+                    NESTED_COLL_CONSTR => {
+                        Ok(SType::Coll(Box::new(SType::Coll(Box::new(element(self)?)))))
+                    }
+                    OPTION_CONSTR => Ok(SType::Option(Box::new(element(self)?))),
+                    OPTION_COLL_CONSTR => Ok(SType::Option(Box::new(SType::Coll(Box::new(
+                        element(self)?,
+                    ))))),
+                    /* (prim, _) */
+                    PAIR1_CONSTR => {
+                        let first = element(self)?;
+                        Ok(SType::Tuple(vec![first, self.read_type()?]))
+                    }
+                    /* Triple when no primitive is given, (_, prim) otherwise */
+                    PAIR2_CONSTR if prim == 0 => self.read_types(3),
+                    PAIR2_CONSTR => {
+                        let first = self.read_type()?;
+                        Ok(SType::Tuple(vec![first, embeddable_type(prim)?]))
+                    }
+                    /* Quadruple when no primitive is given, (prim, prim) otherwise */
+                    PAIR_SYMMETRIC_CONSTR if prim == 0 => self.read_types(4),
+                    PAIR_SYMMETRIC_CONSTR => {
+                        let tpe = embeddable_type(prim)?;
+                        Ok(SType::Tuple(vec![tpe.clone(), tpe]))
+                    }
+                    _ => Err(format!("unknown type code {}", code)),
+                }
+            }
+            TUPLE_TYPE_CODE => {
+                let length = self.byte()? as usize;
+                self.read_types(length)
+            }
+            97 => Ok(SType::Other("Any")),
+            98 => Ok(SType::Unit),
+            99 => Ok(SType::Other("Box")),
+            100 => Ok(SType::Other("AvlTree")),
+            101 => Ok(SType::Other("Context")),
+            102 => Ok(SType::Other("String")),
+            104 => Ok(SType::Other("Header")),
+            105 => Ok(SType::Other("PreHeader")),
+            106 => Ok(SType::Other("Global")),
+            _ => Err(format!("unknown type code {}", code)),
+        }
+    }
+
+    fn read_types(&mut self, length: usize) -> Result<SType, String> {
+        let types = (0..length)
+            .map(|_| self.read_type())
+            .collect::<Result<_, _>>()?;
+        Ok(SType::Tuple(types))
+    }
+
+    fn read_value(&mut self, tpe: &SType) -> Result<SValue, String> {
+        match tpe {
+            SType::Boolean => Ok(SValue::Boolean(self.byte()? != 0)),
+            SType::Byte => Ok(SValue::Byte(self.byte()? as i8)),
+            SType::Short => Ok(SValue::Short(self.zigzag()? as i16)),
+            SType::Int => Ok(SValue::Int(self.zigzag()? as i32)),
+            SType::Long => Ok(SValue::Long(self.zigzag()?)),
+            SType::BigInt => {
+                let length = self.length()?;
+                Ok(SValue::BigInt(self.take(length)?.to_vec()))
+            }
+            SType::GroupElement => Ok(SValue::GroupElement(
+                self.take(GROUP_ELEMENT_LENGTH)?.to_vec(),
+            )),
+            SType::SigmaProp => {
+                let start = self.position;
+                self.skip_sigma_boolean()?;
+                Ok(SValue::SigmaProp(self.bytes[start..self.position].to_vec()))
+            }
+            SType::Unit => Ok(SValue::Unit),
+            SType::Coll(element) => {
+                let length = self.length()?;
+                match element.as_ref() {
+                    SType::Byte => Ok(SValue::Bytes(self.take(length)?.to_vec())),
+                    /* Booleans are packed 8 per byte, least significant bit first */
+                    SType::Boolean => {
+                        let bits = self.take(length.div_ceil(8))?;
+                        Ok(SValue::Coll(
+                            (0..length)
+                                .map(|index| {
+                                    SValue::Boolean(bits[index / 8] >> (index % 8) & 1 == 1)
+                                })
+                                .collect(),
+                        ))
+                    }
+                    /* Every other element takes at least a byte, except Unit which is rare
+                     * enough in collections to be held to the same bound */
+                    _ if length > self.remaining() => {
+                        Err("collection longer than the constant".to_string())
+                    }
+                    element => Ok(SValue::Coll(
+                        (0..length)
+                            .map(|_| self.read_value(element))
+                            .collect::<Result<_, _>>()?,
+                    )),
+                }
+            }
+            SType::Option(element) => match self.byte()? {
+                0 => Ok(SValue::Option(None)),
+                _ => Ok(SValue::Option(Some(Box::new(self.read_value(element)?)))),
+            },
+            SType::Tuple(types) => Ok(SValue::Tuple(
+                types
+                    .iter()
+                    .map(|tpe| self.read_value(tpe))
+                    .collect::<Result<_, _>>()?,
+            )),
+            SType::Other(name) => Err(format!("decoding {} values is not supported", name)),
+        }
+    }
+
+    fn skip_sigma_boolean(&mut self) -> Result<(), String> {
+        match self.byte()? {
+            PROVE_DLOG => {
+                self.take(GROUP_ELEMENT_LENGTH)?;
+            }
+            PROVE_DH_TUPLE => {
+                self.take(4 * GROUP_ELEMENT_LENGTH)?;
+            }
+            TRIVIAL_FALSE | TRIVIAL_TRUE => {}
+            CAND | COR => {
+                for _ in 0..self.length()? {
+                    self.skip_sigma_boolean()?;
+                }
+            }
+            CTHRESHOLD => {
+                let _k = self.length()?;
+                for _ in 0..self.length()? {
+                    self.skip_sigma_boolean()?;
+                }
+            }
+            op_code => return Err(format!("unknown sigma proposition {:#x}", op_code)),
+        }
+        Ok(())
+    }
+}
+
+fn embeddable_type(code: u8) -> Result<SType, String> {
+    match code {
+        1 => Ok(SType::Boolean),
+        2 => Ok(SType::Byte),
+        3 => Ok(SType::Short),
+        4 => Ok(SType::Int),
+        5 => Ok(SType::Long),
+        6 => Ok(SType::BigInt),
+        7 => Ok(SType::GroupElement),
+        8 => Ok(SType::SigmaProp),
+        code => Err(format!("unknown primitive type code {}", code)),
+    }
+}
+
+impl fmt::Display for SType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SType::Boolean => write!(f, "Boolean"),
+            SType::Byte => write!(f, "Byte"),
+            SType::Short => write!(f, "Short"),
+            SType::Int => write!(f, "Int"),
+            SType::Long => write!(f, "Long"),
+            SType::BigInt => write!(f, "BigInt"),
+            SType::GroupElement => write!(f, "GroupElement"),
+            SType::SigmaProp => write!(f, "SigmaProp"),
+            SType::Unit => write!(f, "Unit"),
+            SType::Coll(element) => write!(f, "Coll[{}]", element),
+            SType::Option(element) => write!(f, "Option[{}]", element),
+            SType::Tuple(types) => write!(f, "({})", join(types)),
+            SType::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl fmt::Display for SValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SValue::Boolean(value) => write!(f, "{}", value),
+            SValue::Byte(value) => write!(f, "{}", value),
+            SValue::Short(value) => write!(f, "{}", value),
+            SValue::Int(value) => write!(f, "{}", value),
+            SValue::Long(value) => write!(f, "{}", value),
+            SValue::BigInt(bytes)
+            | SValue::GroupElement(bytes)
+            | SValue::SigmaProp(bytes)
+            | SValue::Bytes(bytes) => write!(f, "{}", encode_hex(bytes)),
+            SValue::Unit => write!(f, "()"),
+            SValue::Coll(values) => write!(f, "[{}]", join(values)),
+            SValue::Option(None) => write!(f, "None"),
+            SValue::Option(Some(value)) => write!(f, "Some({})", value),
+            SValue::Tuple(values) => write!(f, "({})", join(values)),
+        }
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(serialized: &str) -> Constant {
+        decode_constant(serialized).unwrap()
+    }
+
+    #[test]
+    fn decodes_zigzag_integers() {
+        assert_eq!(decode("0402").value, SValue::Int(1));
+        assert_eq!(decode("0401").value, SValue::Int(-1));
+        assert_eq!(decode("04ac02").value, SValue::Int(150));
+        assert_eq!(decode("05a0c21e").value, SValue::Long(250000));
+        assert_eq!(decode("0303").value, SValue::Short(-2));
+    }
+
+    #[test]
+    fn decodes_byte_and_boolean_collections() {
+        let constant = decode("0e03010203");
+        assert_eq!(constant.tpe, SType::Coll(Box::new(SType::Byte)));
+        assert_eq!(constant.value, SValue::Bytes(vec![1, 2, 3]));
+        assert_eq!(constant.value.to_string(), "010203");
+
+        assert_eq!(
+            decode("0d0305").value,
+            SValue::Coll(vec![
+                SValue::Boolean(true),
+                SValue::Boolean(false),
+                SValue::Boolean(true)
+            ])
+        );
+    }
+
+    #[test]
+    fn decodes_group_elements_and_sigma_props() {
+        let point = format!("02{}", "ab".repeat(32));
+        let constant = decode(&format!("07{}", point));
+        assert_eq!(constant.tpe, SType::GroupElement);
+        assert_eq!(constant.value.to_string(), point);
+
+        let constant = decode(&format!("08cd{}", point));
+        assert_eq!(constant.tpe, SType::SigmaProp);
+        assert_eq!(constant.value.to_string(), format!("cd{}", point));
+    }
+
+    #[test]
+    fn decodes_options_and_tuples() {
+        assert_eq!(
+            decode("280102").value,
+            SValue::Option(Some(Box::new(SValue::Int(1))))
+        );
+        assert_eq!(decode("2800").value, SValue::Option(None));
+
+        /* (Int, Long) */
+        let constant = decode("40050204");
+        assert_eq!(constant.tpe, SType::Tuple(vec![SType::Int, SType::Long]));
+        assert_eq!(
+            constant.value,
+            SValue::Tuple(vec![SValue::Int(1), SValue::Long(2)])
+        );
+        /* (Int, Int) */
+        assert_eq!(
+            decode("580204").value,
+            SValue::Tuple(vec![SValue::Int(1), SValue::Int(2)])
+        );
+        /* (Int, Long, Boolean) */
+        let constant = decode("6003040501020401");
+        assert_eq!(constant.tpe.to_string(), "(Int, Long, Boolean)");
+        assert_eq!(constant.value.to_string(), "(1, 2, true)");
+    }
+
+    #[test]
+    fn decodes_eip4_token_registers() {
+        /* R4 name, R5 description and R6 decimals, all UTF-8 in a Coll[Byte] */
+        assert_eq!(decode("0e0454455354").as_text(), Some("TEST".to_string()));
+        assert_eq!(
+            decode("0e0a4120746573742074696b").as_text(),
+            Some("A test tik".to_string())
+        );
+        assert_eq!(decode("0e0139").as_text(), Some("9".to_string()));
+        assert_eq!(decode("0e020001").as_text(), None);
+        assert_eq!(decode("0402").as_text(), None);
+    }
+
+    #[test]
+    fn rejects_truncated_and_malformed_constants() {
+        for serialized in [
+            "",
+            "0g",
+            "040",
+            "ff",
+            "0e050102",
+            "0702ab",
+            "08cd02",
+            "0d0901",
+            "020101",
+            "2801",
+            "04ffffffffffffffffffff",
+        ] {
+            assert!(
+                decode_constant(serialized).is_err(),
+                "{} should not decode",
+                serialized
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_lengths_past_the_constant() {
+        /* Coll[Byte] of u64::MAX bytes */
+        assert!(decode_constant("0effffffffffffffffff01").is_err());
+        /* Coll[Unit] and Coll[Coll[Int]] of 2^32 - 1 elements */
+        assert!(decode_constant("0c62ffffffff0f").is_err());
+        assert!(decode_constant("1cffffffff0f").is_err());
+    }
+}